@@ -1,8 +1,17 @@
-use shu_net_keeper::config::{APPConfig, validate_config};
-use shu_net_keeper::core;
+use shu_net_keeper::config::credentials::{self, SystemKeyring};
+use shu_net_keeper::config::migration;
+use shu_net_keeper::config::{
+    APPConfig, APPConfigValidated, AccountRule, check_username, config_schema, resolve_secrets,
+    validate_config,
+};
+use shu_net_keeper::daemon::{self, CheckOutcome, Daemon, OutageLog, StatsSummary};
+use shu_net_keeper::error::AppError;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
-use tauri::menu::{Menu, MenuItem};
+use std::time::{Duration, Instant};
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
 use tauri::{AppHandle, Emitter, Manager, State};
 use tauri_plugin_autostart::MacosLauncher;
@@ -10,6 +19,10 @@ use tauri_plugin_store::StoreExt;
 
 const STORE_FILE: &str = "settings.json";
 const CONFIG_KEY: &str = "config";
+/// 配置版本号，每次保存配置时加一
+const CONFIG_VERSION_KEY: &str = "config_version";
+/// 密钥环条目名称前缀，与 CLI 使用的条目区分
+const KEYRING_PREFIX: &str = "gui/";
 
 // ─── Shared state ────────────────────────────────────────────────────────────
 
@@ -21,6 +34,11 @@ pub struct DaemonStatus {
     pub last_check: Option<String>,
     pub last_error: Option<String>,
     pub login_count: u32,
+    pub paused: bool,
+    /// 定时暂停的剩余秒数（未暂停或暂停直到手动恢复时为 None）
+    pub pause_remaining_secs: Option<i64>,
+    /// 守护线程当前使用的配置版本（未运行时为 None）
+    pub config_version: Option<u64>,
 }
 
 impl Default for DaemonStatus {
@@ -32,14 +50,30 @@ impl Default for DaemonStatus {
             last_check: None,
             last_error: None,
             login_count: 0,
+            paused: false,
+            pause_remaining_secs: None,
+            config_version: None,
         }
     }
 }
 
+/// 发给守护线程的命令
+enum DaemonCommand {
+    /// 暂停自动登录（分钟数，None 表示直到手动恢复）
+    Pause(Option<u64>),
+    Resume,
+    /// 应用新保存的配置
+    ApplyConfig {
+        config: Box<APPConfigValidated>,
+        version: u64,
+    },
+}
+
 pub struct AppState {
     pub daemon_running: Arc<AtomicBool>,
     pub status: Arc<Mutex<DaemonStatus>>,
     pub logs: Arc<Mutex<Vec<String>>>,
+    commands: Mutex<Option<Sender<DaemonCommand>>>,
 }
 
 // ─── Helpers ─────────────────────────────────────────────────────────────────
@@ -67,134 +101,207 @@ fn emit_status(app_handle: &AppHandle, status: &Arc<Mutex<DaemonStatus>>) {
     let _ = app_handle.emit("status-update", s);
 }
 
-fn load_config_from_store(app_handle: &AppHandle) -> Result<APPConfig, String> {
+/// 去掉 AppError 的分类前缀，只保留具体错误信息
+fn error_message(err: &AppError) -> String {
+    match err {
+        AppError::Login(e) => e.to_string(),
+        AppError::Network(e) => e.to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn data_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("创建数据目录失败: {}", e))?;
+    Ok(dir)
+}
+
+/// 向运行中的守护线程发送命令
+fn send_command(state: &AppState, command: DaemonCommand) -> Result<(), String> {
+    if !state.daemon_running.load(Ordering::SeqCst) {
+        return Err("守护进程未运行".to_string());
+    }
+    match state.commands.lock().unwrap().as_ref() {
+        Some(commands) => commands
+            .send(command)
+            .map_err(|_| "守护进程未运行".to_string()),
+        None => Err("守护进程未运行".to_string()),
+    }
+}
+
+/// 同步暂停状态到 DaemonStatus
+fn sync_pause_status(daemon: &Daemon, status: &Arc<Mutex<DaemonStatus>>) {
+    let snapshot = daemon.status();
+    let mut s = status.lock().unwrap();
+    s.paused = snapshot.paused;
+    s.pause_remaining_secs = snapshot.pause_remaining_secs;
+}
+
+/// 读取已保存的配置及其版本号；配置格式较旧时升级到当前格式并写回
+fn read_stored_config(app_handle: &AppHandle) -> Result<Option<(APPConfig, u64)>, String> {
     let store = app_handle.store(STORE_FILE).map_err(|e| e.to_string())?;
-    let value = store
-        .get(CONFIG_KEY)
+    let Some(value) = store.get(CONFIG_KEY) else {
+        return Ok(None);
+    };
+    let (config, from_version) =
+        migration::parse_json(value).map_err(|e| format!("配置解析失败: {}", e))?;
+    if from_version < migration::CONFIG_VERSION {
+        let value = serde_json::to_value(&config).map_err(|e| e.to_string())?;
+        store.set(CONFIG_KEY, value);
+        store.save().map_err(|e| e.to_string())?;
+    }
+
+    let version = store
+        .get(CONFIG_VERSION_KEY)
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+    Ok(Some((config, version)))
+}
+
+/// 读取守护线程使用的配置（解析密码来源）及其版本号
+fn load_config_from_store(app_handle: &AppHandle) -> Result<(APPConfig, u64), String> {
+    let (mut config, version) = read_stored_config(app_handle)?
         .ok_or_else(|| "尚未保存配置，请先在「配置」页填写并保存".to_string())?;
-    serde_json::from_value(value).map_err(|e| format!("配置解析失败: {}", e))
+    resolve_secrets(&mut config, &data_dir(app_handle)?).map_err(|e| e.to_string())?;
+    Ok((config, version))
 }
 
 // ─── Daemon loop ─────────────────────────────────────────────────────────────
 
 fn daemon_loop(
-    config: shu_net_keeper::config::APPConfigValidated,
+    mut daemon: Daemon,
+    config_version: u64,
+    commands: Receiver<DaemonCommand>,
     running: Arc<AtomicBool>,
     status: Arc<Mutex<DaemonStatus>>,
     logs: Arc<Mutex<Vec<String>>>,
     app_handle: AppHandle,
 ) {
-    let mut last_ip: Option<String> = None;
-
     {
         let mut s = status.lock().unwrap();
         s.running = true;
+        s.login_count = daemon.state().login_count;
+        s.config_version = Some(config_version);
     }
+    sync_pause_status(&daemon, &status);
     add_log(&logs, &app_handle, "守护进程已启动");
     emit_status(&app_handle, &status);
 
     while running.load(Ordering::SeqCst) {
         add_log(&logs, &app_handle, "正在检查网络连接状态...");
 
-        match core::network::check_network_connection(&mut last_ip) {
-            Ok(true) => {
-                let ip = last_ip.clone();
-                add_log(
-                    &logs,
-                    &app_handle,
-                    &format!("✓ 网络连接正常，IP: {}", ip.as_deref().unwrap_or("未知")),
-                );
-                {
-                    let mut s = status.lock().unwrap();
-                    s.connected = true;
-                    s.ip = ip;
-                    s.last_check = Some(now_str());
-                    s.last_error = None;
+        let result = daemon.check();
+        let ip = daemon.state().last_ip_address.clone();
+
+        match &result {
+            Ok(CheckOutcome::Online) => add_log(
+                &logs,
+                &app_handle,
+                &format!("✓ 网络连接正常，IP: {}", ip.as_deref().unwrap_or("未知")),
+            ),
+            Ok(CheckOutcome::LoggedIn {
+                ip, notification, ..
+            }) => {
+                add_log(&logs, &app_handle, &format!("✓ 登录成功，IP: {}", ip));
+                match notification {
+                    Some(Ok(())) => add_log(&logs, &app_handle, "✓ 邮件通知已发送"),
+                    Some(Err(e)) => add_log(&logs, &app_handle, &format!("✗ 邮件发送失败: {}", e)),
+                    None => {}
                 }
-                emit_status(&app_handle, &status);
             }
-            Ok(false) => {
-                add_log(&logs, &app_handle, "网络未连接，尝试登录...");
-                match core::login::network_login(&config.username, &config.password) {
-                    Ok(()) => {
-                        let current_ip = core::network::get_host_ip()
-                            .ok()
-                            .flatten()
-                            .unwrap_or_else(|| "未知".to_string());
-                        let ip_changed = matches!(&last_ip, Some(old) if old != &current_ip);
-
-                        add_log(
-                            &logs,
-                            &app_handle,
-                            &format!("✓ 登录成功，IP: {}", current_ip),
-                        );
-
-                        if let Some(smtp) = &config.smtp {
-                            match core::email::send_login_notification(
-                                smtp,
-                                &config.username,
-                                &current_ip,
-                                ip_changed,
-                            ) {
-                                Ok(()) => add_log(&logs, &app_handle, "✓ 邮件通知已发送"),
-                                Err(e) => {
-                                    add_log(&logs, &app_handle, &format!("✗ 邮件发送失败: {}", e))
-                                }
-                            }
-                        }
-
-                        {
-                            let mut s = status.lock().unwrap();
-                            s.connected = true;
-                            s.ip = Some(current_ip.clone());
-                            s.last_check = Some(now_str());
-                            s.last_error = None;
-                            s.login_count += 1;
-                        }
-                        last_ip = Some(current_ip);
-                        emit_status(&app_handle, &status);
-                    }
-                    Err(e) => {
-                        add_log(&logs, &app_handle, &format!("✗ 登录失败: {}", e));
-                        {
-                            let mut s = status.lock().unwrap();
-                            s.connected = false;
-                            s.last_check = Some(now_str());
-                            s.last_error = Some(e.to_string());
-                        }
-                        emit_status(&app_handle, &status);
-                    }
-                }
+            Ok(CheckOutcome::Paused) => add_log(&logs, &app_handle, "⏸ 自动登录已暂停，当前未登录"),
+            Ok(CheckOutcome::Idle) => {
+                add_log(&logs, &app_handle, "💤 当前网络不匹配任何网络配置，空闲中")
             }
-            Err(e) => {
-                add_log(&logs, &app_handle, &format!("✗ 网络检查失败: {}", e));
-                {
-                    let mut s = status.lock().unwrap();
-                    s.connected = false;
-                    s.last_check = Some(now_str());
-                    s.last_error = Some(e.to_string());
+            Err(e @ AppError::Login(_)) => add_log(
+                &logs,
+                &app_handle,
+                &format!("✗ 登录失败: {}", error_message(e)),
+            ),
+            Err(e) => add_log(
+                &logs,
+                &app_handle,
+                &format!("✗ 网络检查失败: {}", error_message(e)),
+            ),
+        }
+
+        {
+            let mut s = status.lock().unwrap();
+            s.connected = matches!(
+                result,
+                Ok(CheckOutcome::Online | CheckOutcome::LoggedIn { .. })
+            );
+            s.last_check = Some(now_str());
+            s.login_count = daemon.state().login_count;
+            match &result {
+                Ok(_) => {
+                    s.ip = ip;
+                    s.last_error = None;
                 }
-                emit_status(&app_handle, &status);
+                Err(e) => s.last_error = Some(error_message(e)),
             }
         }
+        sync_pause_status(&daemon, &status);
+        emit_status(&app_handle, &status);
 
-        let interval = config.interval;
+        let interval = daemon.interval();
         add_log(
             &logs,
             &app_handle,
             &format!("等待 {} 秒后再次检查...", interval),
         );
-        for _ in 0..interval {
+        // 每次都按当前配置计算，应用新配置后检查间隔立即生效
+        let started = Instant::now();
+        while started.elapsed() < Duration::from_secs(daemon.interval()) {
             if !running.load(Ordering::SeqCst) {
                 break;
             }
-            std::thread::sleep(std::time::Duration::from_secs(1));
+            while let Ok(command) = commands.try_recv() {
+                match command {
+                    DaemonCommand::Pause(minutes) => {
+                        let message = match (daemon.pause(minutes), minutes) {
+                            (Err(e), _) => format!("✗ 暂停失败: {}", error_message(&e)),
+                            (Ok(()), Some(minutes)) => {
+                                format!("⏸ 已暂停自动登录 {} 分钟", minutes)
+                            }
+                            (Ok(()), None) => "⏸ 已暂停自动登录，直到手动恢复".to_string(),
+                        };
+                        add_log(&logs, &app_handle, &message);
+                    }
+                    DaemonCommand::Resume => {
+                        daemon.resume();
+                        add_log(&logs, &app_handle, "▶ 已恢复自动登录");
+                    }
+                    DaemonCommand::ApplyConfig { config, version } => {
+                        daemon.set_config(*config);
+                        status.lock().unwrap().config_version = Some(version);
+                        add_log(
+                            &logs,
+                            &app_handle,
+                            &format!("✓ 已应用新配置（版本 {}）", version),
+                        );
+                    }
+                }
+                sync_pause_status(&daemon, &status);
+                emit_status(&app_handle, &status);
+            }
+            // 每秒刷新暂停剩余时间
+            sync_pause_status(&daemon, &status);
+            std::thread::sleep(Duration::from_secs(1));
         }
     }
 
+    // 先释放引擎（及实例锁），再报告已停止，保证随后重新启动不会撞上自己的锁
+    drop(daemon);
     {
         let mut s = status.lock().unwrap();
         s.running = false;
+        s.paused = false;
+        s.pause_remaining_secs = None;
+        s.config_version = None;
     }
     add_log(&logs, &app_handle, "守护进程已停止");
     emit_status(&app_handle, &status);
@@ -204,23 +311,94 @@ fn daemon_loop(
 
 #[tauri::command]
 fn get_config(app_handle: AppHandle) -> Result<Option<APPConfig>, String> {
-    let store = app_handle.store(STORE_FILE).map_err(|e| e.to_string())?;
-    match store.get(CONFIG_KEY) {
-        Some(v) => {
-            let cfg: APPConfig = serde_json::from_value(v).map_err(|e| e.to_string())?;
+    match read_stored_config(&app_handle)? {
+        Some((mut cfg, _)) => {
+            credentials::restore_passwords_from_store(&mut cfg, &SystemKeyring)
+                .map_err(|e| e.to_string())?;
             Ok(Some(cfg))
         }
         None => Ok(None),
     }
 }
 
+#[derive(Debug, serde::Serialize)]
+pub struct SaveResult {
+    /// 保存后的配置版本
+    pub version: u64,
+    /// 是否已应用到运行中的守护线程
+    pub applied: bool,
+}
+
+/// 保存配置；守护线程运行时验证新配置并立即应用
+/// 验证失败时配置仍会保存，但守护线程继续使用原配置并返回错误
 #[tauri::command]
-fn save_config(config: APPConfig, app_handle: AppHandle) -> Result<(), String> {
+fn save_config(
+    mut config: APPConfig,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> Result<SaveResult, String> {
+    // 在密码移入密钥环之前准备运行时配置
+    let validated = if state.daemon_running.load(Ordering::SeqCst) {
+        let mut runtime = config.clone();
+        Some(
+            resolve_secrets(&mut runtime, &data_dir(&app_handle)?)
+                .and_then(|_| validate_config(&runtime)),
+        )
+    } else {
+        None
+    };
+
+    // 启用 keyring 功能时密码保存到系统密钥环，settings.json 中只记录条目名称
+    if credentials::KEYRING_ENABLED {
+        credentials::move_passwords_to_store(&mut config, &SystemKeyring, KEYRING_PREFIX)
+            .map_err(|e| e.to_string())?;
+    }
     let store = app_handle.store(STORE_FILE).map_err(|e| e.to_string())?;
     let value = serde_json::to_value(&config).map_err(|e| e.to_string())?;
+    let version = store
+        .get(CONFIG_VERSION_KEY)
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0)
+        + 1;
     store.set(CONFIG_KEY, value);
+    store.set(CONFIG_VERSION_KEY, version);
     store.save().map_err(|e| e.to_string())?;
-    Ok(())
+
+    match validated {
+        None => Ok(SaveResult {
+            version,
+            applied: false,
+        }),
+        Some(Ok(validated)) => {
+            send_command(
+                &state,
+                DaemonCommand::ApplyConfig {
+                    config: Box::new(validated),
+                    version,
+                },
+            )?;
+            Ok(SaveResult {
+                version,
+                applied: true,
+            })
+        }
+        Some(Err(e)) => Err(format!(
+            "配置已保存，但验证失败，守护进程继续使用原配置: {}",
+            e
+        )),
+    }
+}
+
+/// 配置的 JSON Schema，前端据此设置表单约束并在保存前检查
+#[tauri::command]
+fn get_config_schema() -> serde_json::Value {
+    config_schema()
+}
+
+/// 按账号规则检查用户名，供表单实时提示（与配置验证使用同一套规则）
+#[tauri::command]
+fn validate_username(username: String, account: AccountRule) -> Result<(), String> {
+    check_username(&username, &account)
 }
 
 #[tauri::command]
@@ -229,9 +407,13 @@ fn start_daemon(state: State<'_, AppState>, app_handle: AppHandle) -> Result<(),
         return Err("守护进程已在运行".to_string());
     }
 
-    let config = load_config_from_store(&app_handle)?;
+    let (config, config_version) = load_config_from_store(&app_handle)?;
     let validated = validate_config(&config).map_err(|e| format!("配置验证失败: {}", e))?;
 
+    let daemon = Daemon::new(validated, &data_dir(&app_handle)?).map_err(|e| e.to_string())?;
+
+    let (commands, receiver) = mpsc::channel();
+    *state.commands.lock().unwrap() = Some(commands);
     state.daemon_running.store(true, Ordering::SeqCst);
 
     let running = Arc::clone(&state.daemon_running);
@@ -239,7 +421,15 @@ fn start_daemon(state: State<'_, AppState>, app_handle: AppHandle) -> Result<(),
     let logs = Arc::clone(&state.logs);
 
     std::thread::spawn(move || {
-        daemon_loop(validated, running, status, logs, app_handle);
+        daemon_loop(
+            daemon,
+            config_version,
+            receiver,
+            running,
+            status,
+            logs,
+            app_handle,
+        );
     });
 
     Ok(())
@@ -251,6 +441,16 @@ fn stop_daemon(state: State<'_, AppState>) -> Result<(), String> {
     Ok(())
 }
 
+#[tauri::command]
+fn pause_daemon(state: State<'_, AppState>, minutes: Option<u64>) -> Result<(), String> {
+    send_command(&state, DaemonCommand::Pause(minutes))
+}
+
+#[tauri::command]
+fn resume_daemon(state: State<'_, AppState>) -> Result<(), String> {
+    send_command(&state, DaemonCommand::Resume)
+}
+
 #[tauri::command]
 fn get_status(state: State<'_, AppState>) -> DaemonStatus {
     state.status.lock().unwrap().clone()
@@ -261,6 +461,24 @@ fn get_logs(state: State<'_, AppState>) -> Vec<String> {
     state.logs.lock().unwrap().clone()
 }
 
+#[derive(Debug, serde::Serialize)]
+pub struct StatsReport {
+    pub current_outage: Option<daemon::Outage>,
+    pub daily: Vec<StatsSummary>,
+    pub weekly: Vec<StatsSummary>,
+}
+
+#[tauri::command]
+fn get_stats(app_handle: AppHandle) -> Result<StatsReport, String> {
+    let log = OutageLog::load(&daemon::get_stats_path(&data_dir(&app_handle)?));
+    let now = chrono::Local::now();
+    Ok(StatsReport {
+        current_outage: log.current().cloned(),
+        daily: log.daily(7, now),
+        weekly: log.weekly(4, now),
+    })
+}
+
 #[tauri::command]
 fn get_autostart(app_handle: AppHandle) -> bool {
     use tauri_plugin_autostart::ManagerExt;
@@ -293,12 +511,29 @@ pub fn run() {
                 daemon_running: Arc::new(AtomicBool::new(false)),
                 status: Arc::new(Mutex::new(DaemonStatus::default())),
                 logs: Arc::new(Mutex::new(Vec::new())),
+                commands: Mutex::new(None),
             });
 
             // ── System tray ───────────────────────────────────────────────
             let show_item = MenuItem::with_id(app, "show", "显示窗口", true, None::<&str>)?;
+            let pause_30_item =
+                MenuItem::with_id(app, "pause_30", "暂停自动登录 30 分钟", true, None::<&str>)?;
+            let pause_item =
+                MenuItem::with_id(app, "pause", "暂停自动登录（直到恢复）", true, None::<&str>)?;
+            let resume_item = MenuItem::with_id(app, "resume", "恢复自动登录", true, None::<&str>)?;
             let quit_item = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show_item, &quit_item])?;
+            let menu = Menu::with_items(
+                app,
+                &[
+                    &show_item,
+                    &PredefinedMenuItem::separator(app)?,
+                    &pause_30_item,
+                    &pause_item,
+                    &resume_item,
+                    &PredefinedMenuItem::separator(app)?,
+                    &quit_item,
+                ],
+            )?;
 
             let icon = app
                 .default_window_icon()
@@ -333,6 +568,18 @@ pub fn run() {
                             let _ = win.set_focus();
                         }
                     }
+                    id @ ("pause_30" | "pause" | "resume") => {
+                        let command = match id {
+                            "pause_30" => DaemonCommand::Pause(Some(30)),
+                            "pause" => DaemonCommand::Pause(None),
+                            _ => DaemonCommand::Resume,
+                        };
+                        if let Some(state) = app.try_state::<AppState>()
+                            && let Err(e) = send_command(&state, command)
+                        {
+                            add_log(&state.logs, app, &format!("✗ {}", e));
+                        }
+                    }
                     "quit" => {
                         if let Some(state) = app.try_state::<AppState>() {
                             state.daemon_running.store(false, Ordering::SeqCst);
@@ -355,10 +602,15 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             get_config,
             save_config,
+            validate_username,
+            get_config_schema,
             start_daemon,
             stop_daemon,
+            pause_daemon,
+            resume_daemon,
             get_status,
             get_logs,
+            get_stats,
             get_autostart,
             set_autostart,
         ])
@@ -0,0 +1,120 @@
+use super::print_json;
+use serde_json::json;
+use shu_net_keeper::config::{self, APPConfigValidated};
+use shu_net_keeper::core::email;
+use shu_net_keeper::daemon;
+use shu_net_keeper::error::{AppError, ConfigError, Result};
+
+/// 加载并验证配置文件，输出配置摘要
+pub fn check(json: bool) -> Result<()> {
+    let loaded = config::find_config()
+        .and_then(|(path, source)| config::load_config().map(|config| (path, source, config)));
+    let (path, source, config) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            if json {
+                let issues = match &e {
+                    ConfigError::Invalid { issues } => issues.as_slice(),
+                    _ => &[],
+                };
+                print_json(&json!({
+                    "path": config::get_config_path(),
+                    "valid": false,
+                    "error": e.to_string(),
+                    "issues": issues,
+                }))?;
+            }
+            return Err(e.into());
+        }
+    };
+
+    let hooks = configured_hooks(&config);
+    let profiles: Vec<&str> = config.profiles.iter().map(|p| p.name.as_str()).collect();
+    // 只有配置了网络配置时才检测当前网络
+    let active_profile = if profiles.is_empty() {
+        None
+    } else {
+        daemon::select_profile(&config.profiles).map(|p| p.name.as_str())
+    };
+    if json {
+        return print_json(&json!({
+            "path": path,
+            "source": source.to_string(),
+            "valid": true,
+            "username": config.username,
+            "interval": config.interval,
+            "smtp_enabled": config.smtp.is_some(),
+            "hooks": hooks,
+            "http_bind": config.http.as_ref().map(|http| http.bind.to_string()),
+            "profiles": profiles,
+            "active_profile": active_profile,
+        }));
+    }
+
+    println!("✓ 配置文件有效: {}（{}）", path.display(), source);
+    println!("账号:     {}", config.username);
+    println!("检查间隔: {} 秒", config.interval);
+    match &config.smtp {
+        Some(smtp) => println!(
+            "邮件通知: 已启用（{}:{} → {}）",
+            smtp.server, smtp.port, smtp.receiver
+        ),
+        None => println!("邮件通知: 未启用"),
+    }
+    if hooks.is_empty() {
+        println!("事件钩子: 未配置");
+    } else {
+        println!("事件钩子: {}", hooks.join(", "));
+    }
+    match &config.http {
+        Some(http) => println!("HTTP 服务: {}", http.bind),
+        None => println!("HTTP 服务: 未启用"),
+    }
+    if !profiles.is_empty() {
+        println!("网络配置: {}", profiles.join(", "));
+        match active_profile {
+            Some(name) => println!("当前网络: 匹配 {}", name),
+            None => println!("当前网络: 不匹配任何网络配置（守护进程将空闲）"),
+        }
+    }
+    Ok(())
+}
+
+/// 输出配置文件的 JSON Schema（总是 JSON 格式）
+pub fn schema() -> Result<()> {
+    print_json(&config::config_schema())
+}
+
+/// 发送测试邮件
+pub fn test_notify(json: bool) -> Result<()> {
+    let config = config::load_config()?;
+    let Some(smtp) = &config.smtp else {
+        return Err(AppError::Other(
+            "未启用邮件通知，请在配置文件中设置 smtp_enabled = true 并填写 [smtp]".to_string(),
+        ));
+    };
+
+    email::send_test_notification(smtp, &config.username)?;
+
+    if json {
+        return print_json(&json!({ "sent": true, "receiver": smtp.receiver }));
+    }
+    println!("✓ 测试邮件已发送到 {}", smtp.receiver);
+    Ok(())
+}
+
+/// 已配置的事件钩子名称
+fn configured_hooks(config: &APPConfigValidated) -> Vec<&'static str> {
+    let hooks = &config.hooks;
+    [
+        ("on_login_success", &hooks.on_login_success),
+        ("on_login_failure", &hooks.on_login_failure),
+        ("on_ip_changed", &hooks.on_ip_changed),
+        ("on_offline", &hooks.on_offline),
+        ("on_online", &hooks.on_online),
+    ]
+    .into_iter()
+    .filter(|(_, command)| command.is_some())
+    .map(|(name, _)| name)
+    .collect()
+}
@@ -0,0 +1,184 @@
+use super::stats::format_duration;
+use clap::Subcommand;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use shu_net_keeper::constants::MAX_PAUSE_MINUTES;
+use shu_net_keeper::core::network::OnlineUserInfo;
+use shu_net_keeper::daemon::StatusSnapshot;
+use shu_net_keeper::daemon::control::{self, ControlRequest, ControlResponse};
+use shu_net_keeper::error::{AppError, ControlError, Result};
+
+#[derive(Subcommand)]
+pub enum CtlCommand {
+    /// 查看守护进程状态
+    Status,
+    /// 查看当前在线用户信息
+    Info,
+    /// 立即检查一次网络
+    Check,
+    /// 立即登录
+    Login,
+    /// 注销当前在线会话
+    Logout,
+    /// 暂停自动登录（仍然持续检查网络状态）
+    Pause {
+        /// 暂停的分钟数，不指定时暂停直到执行 resume
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..=MAX_PAUSE_MINUTES))]
+        minutes: Option<u64>,
+    },
+    /// 恢复自动登录
+    Resume,
+    /// 重新加载配置文件
+    Reload,
+}
+
+impl CtlCommand {
+    fn request(&self) -> ControlRequest {
+        match self {
+            CtlCommand::Status => ControlRequest::Status,
+            CtlCommand::Info => ControlRequest::Info,
+            CtlCommand::Check => ControlRequest::Check,
+            CtlCommand::Login => ControlRequest::Login,
+            CtlCommand::Logout => ControlRequest::Logout,
+            CtlCommand::Pause { minutes } => ControlRequest::Pause { minutes: *minutes },
+            CtlCommand::Resume => ControlRequest::Resume,
+            CtlCommand::Reload => ControlRequest::Reload,
+        }
+    }
+}
+
+/// 通过控制接口向运行中的守护进程发送命令
+pub fn run(command: CtlCommand, json: bool) -> Result<()> {
+    let response = control::send(&control::get_socket_path(), &command.request())?;
+
+    if json {
+        let text = serde_json::to_string_pretty(&response)
+            .map_err(|e| AppError::Other(format!("序列化响应失败: {}", e)))?;
+        println!("{}", text);
+    }
+
+    let data = match response {
+        ControlResponse { ok: true, data, .. } => data.unwrap_or(Value::Null),
+        ControlResponse { error, .. } => {
+            let message = error.unwrap_or_else(|| "未知错误".to_string());
+            return Err(ControlError::Rejected(message).into());
+        }
+    };
+    if json {
+        return Ok(());
+    }
+
+    match command {
+        CtlCommand::Status => print_status(&parse(data)?),
+        CtlCommand::Info => print_info(&parse(data)?),
+        CtlCommand::Check | CtlCommand::Login => print_outcome(&data),
+        CtlCommand::Logout => {
+            println!("✓ 已注销");
+            if data["paused"] != Value::Bool(true) {
+                println!(
+                    "提示: 自动登录未暂停，守护进程会在下一次检查时重新登录（可先执行 ctl pause）"
+                );
+            }
+        }
+        CtlCommand::Pause { .. } => {
+            let status: StatusSnapshot = parse(data)?;
+            match status.pause_remaining_secs {
+                Some(secs) => println!("⏸ 已暂停自动登录，{} 后自动恢复", format_duration(secs)),
+                None => println!("⏸ 已暂停自动登录，直到执行 ctl resume"),
+            }
+        }
+        CtlCommand::Resume => println!("▶ 已恢复自动登录"),
+        CtlCommand::Reload => {
+            let status: StatusSnapshot = parse(data)?;
+            println!(
+                "✓ 配置已重新加载（账号: {}，检查间隔: {} 秒）",
+                status.username, status.interval
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn parse<T: DeserializeOwned>(data: Value) -> Result<T> {
+    serde_json::from_value(data).map_err(|e| ControlError::Protocol(e.to_string()).into())
+}
+
+fn print_status(status: &StatusSnapshot) {
+    let connected = match status.connected {
+        Some(true) => "已连接",
+        Some(false) => "未连接",
+        None => "未知",
+    };
+    let format_time = |t: &Option<chrono::DateTime<chrono::Local>>| {
+        t.map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "—".to_string())
+    };
+
+    println!("账号:     {}", status.username);
+    println!("连接状态: {}", connected);
+    if status.idle {
+        println!("网络配置: 不匹配（空闲，不检查网络）");
+    } else if let Some(profile) = &status.profile {
+        println!("网络配置: {}", profile);
+    }
+    let auto_login = match (status.paused, status.pause_remaining_secs) {
+        (false, _) => "运行中".to_string(),
+        (true, Some(secs)) => format!("已暂停（剩余 {}）", format_duration(secs)),
+        (true, None) => "已暂停（直到手动恢复）".to_string(),
+    };
+    println!("自动登录: {}", auto_login);
+    println!("IP:       {}", status.ip.as_deref().unwrap_or("—"));
+    println!("登录次数: {}", status.login_count);
+    println!("上次登录: {}", format_time(&status.last_login_at));
+    println!("上次检查: {}", format_time(&status.last_check_at));
+    println!("检查间隔: {} 秒", status.interval);
+    if let Some(error) = &status.last_error {
+        println!("最近错误: {}", error);
+    }
+    if let Some(outage) = &status.current_outage {
+        println!(
+            "当前断网: 开始于 {}（{}）",
+            outage.start.format("%Y-%m-%d %H:%M:%S"),
+            outage.cause
+        );
+    }
+}
+
+pub(super) fn print_info(info: &OnlineUserInfo) {
+    let fields = [
+        ("学号", &info.user_id),
+        ("姓名", &info.user_name),
+        ("IP", &info.user_ip),
+        ("MAC", &info.user_mac),
+        ("服务", &info.service),
+        ("用户组", &info.user_group),
+        ("余额", &info.account_fee),
+        ("会话索引", &info.user_index),
+    ];
+    for (label, value) in fields {
+        println!("{}: {}", label, value.as_deref().unwrap_or("—"));
+    }
+    for item in info.portal_items() {
+        println!("{}: {}", item.name, item.value);
+    }
+}
+
+pub(super) fn print_outcome(data: &Value) {
+    match data["outcome"].as_str() {
+        Some("logged_in") => {
+            let ip = data["ip"].as_str().unwrap_or("未知");
+            if data["ip_changed"] == Value::Bool(true) {
+                println!("✓ 登录成功，IP: {}（IP 已变化）", ip);
+            } else {
+                println!("✓ 登录成功，IP: {}", ip);
+            }
+            if let Some(error) = data["notification_error"].as_str() {
+                println!("✗ 邮件发送失败: {}", error);
+            }
+        }
+        Some("paused") => println!("⏸ 自动登录已暂停，当前未登录"),
+        Some("idle") => println!("⏸ 当前网络不匹配任何配置，守护进程空闲"),
+        _ => println!("✓ 网络连接正常"),
+    }
+}
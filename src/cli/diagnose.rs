@@ -0,0 +1,60 @@
+use super::print_json;
+use serde_json::json;
+use shu_net_keeper::core::diagnose::{self, StepStatus};
+use shu_net_keeper::error::{AppError, Result};
+
+/// 逐步诊断登录流程并输出报告，有步骤失败时返回错误
+pub fn run(json: bool) -> Result<()> {
+    let steps = diagnose::run();
+    let failed = steps
+        .iter()
+        .filter(|step| step.status == StepStatus::Fail)
+        .count();
+
+    if json {
+        print_json(&json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "os": std::env::consts::OS,
+            "arch": std::env::consts::ARCH,
+            "time": chrono::Local::now().to_rfc3339(),
+            "steps": steps,
+        }))?;
+    } else {
+        println!("shu-net-keeper 连通性诊断");
+        println!(
+            "版本: {}  系统: {}/{}  时间: {}",
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+        );
+        println!("（IP、MAC 和查询参数已脱敏，可直接粘贴到 issue 中）\n");
+
+        for (index, step) in steps.iter().enumerate() {
+            let mark = match step.status {
+                StepStatus::Pass => "✓",
+                StepStatus::Fail => "✗",
+                StepStatus::Skip => "-",
+            };
+            println!(
+                "{} {}. {}（{} ms）",
+                mark,
+                index + 1,
+                step.name,
+                step.elapsed_ms
+            );
+            for detail in &step.details {
+                println!("      {}", detail);
+            }
+        }
+        println!();
+    }
+
+    if failed > 0 {
+        return Err(AppError::Other(format!("诊断发现 {} 个步骤失败", failed)));
+    }
+    if !json {
+        println!("✓ 所有步骤均通过");
+    }
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use super::print_json;
+use serde_json::json;
+use shu_net_keeper::config::{self, encryption};
+use shu_net_keeper::error::{AppError, Result};
+use shu_net_keeper::fsutil;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+/// 交互式输入密码并输出加密后的 password_encrypted 值，没有密钥时先生成密钥文件
+pub fn run(json: bool) -> Result<()> {
+    let config_path = config::get_config_path();
+    let base_dir = config_path.parent().unwrap_or(Path::new("."));
+    let key_file = encryption::key_file_path(base_dir);
+
+    let key_from_env = env::var(encryption::KEY_ENV).is_ok_and(|key| !key.is_empty());
+    let created = !key_from_env && !key_file.exists();
+    let key = if created {
+        let key = encryption::SecretKey::generate();
+        fs::create_dir_all(base_dir)?;
+        fsutil::write_atomic_private(&key_file, format!("{}\n", key.to_base64()).as_bytes())?;
+        key
+    } else {
+        encryption::load_key(base_dir)?
+    };
+
+    let password = rpassword::prompt_password("要加密的密码（输入时不显示）: ")?;
+    if password.is_empty() {
+        return Err(AppError::Other("密码不能为空".to_string()));
+    }
+    if rpassword::prompt_password("再次输入确认: ")? != password {
+        return Err(AppError::Other("两次输入的密码不一致".to_string()));
+    }
+    let encrypted = key.encrypt(&password);
+
+    if json {
+        return print_json(&json!({
+            "password_encrypted": encrypted,
+            "key_file": (!key_from_env).then_some(&key_file),
+            "key_created": created,
+        }));
+    }
+
+    if created {
+        println!("✓ 已生成密钥文件: {}", key_file.display());
+    }
+    println!("将下面一行写入配置文件（替换 password，SMTP 密码写在 [smtp] 中）:\n");
+    println!("password_encrypted = \"{}\"\n", encrypted);
+    if key_from_env {
+        println!("运行时需要设置相同的环境变量 {}", encryption::KEY_ENV);
+    } else {
+        println!(
+            "请妥善保管密钥文件，运行时也可以通过 {} 指定其位置",
+            encryption::KEY_FILE_ENV
+        );
+    }
+    Ok(())
+}
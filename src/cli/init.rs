@@ -0,0 +1,309 @@
+use super::print_json;
+use serde_json::json;
+use shu_net_keeper::config::{self, APPConfig, SmtpConfig};
+use shu_net_keeper::constants::DEFAULT_CHECK_INTERVAL;
+use shu_net_keeper::error::{AppError, Result};
+use shu_net_keeper::fsutil;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+
+const CONFIG_TEMPLATE: &str = r#"# 配置格式版本，请勿修改
+version = 1
+
+# 必填：校园网账号信息
+username = "your_student_id"
+password = "your_password"
+
+# 可选：检查间隔（秒），默认 10 秒
+interval = 10
+
+# 可选：是否启用 SMTP 邮件通知
+smtp_enabled = false
+
+# 可选：SMTP 邮件通知配置（当 smtp_enabled = true 时需要配置）
+# [smtp]
+# server = "smtp.example.com"        # SMTP 服务器地址
+# port = 465                         # SMTP 端口（推荐使用 465）
+# sender = "your_email@example.com"  # 发件人邮箱
+# password = "your_email_password"   # 邮箱密码或授权码
+# receiver = "recipient@example.com" # 收件人邮箱
+
+# 可选：账号格式规则，默认为 8 位数字学号
+# [account]
+# rule = "length"                    # length / regex / none
+# min_length = 6
+# max_length = 12
+# digits_only = false
+
+# 可选：网络配置，只在匹配的网络中检查和登录（可写多个，第一个匹配的生效）
+# [[profiles]]
+# name = "校园 Wi-Fi"
+# ssid = ["Shu(ForAll)"]              # 也可以用 interface / subnet / gateway 匹配
+"#;
+
+/// 创建配置文件：默认交互式填写，`template` 为 true 时只写入模板
+pub fn run(force: bool, template: bool, json: bool) -> Result<()> {
+    let path = config::get_config_path();
+    if path.exists() && !force {
+        return Err(AppError::Other(format!(
+            "配置文件已存在: {}\n如需覆盖请使用 --force",
+            path.display()
+        )));
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    if template {
+        fsutil::write_atomic_private(&path, CONFIG_TEMPLATE.as_bytes())?;
+        if json {
+            return print_json(&json!({ "path": path }));
+        }
+        println!("✓ 已创建配置文件模板: {}", path.display());
+        println!("请填写 username 和 password 后运行 `shu-net-keeper check-config` 检查配置");
+        return Ok(());
+    }
+
+    let config = wizard(&path)?;
+    fsutil::write_atomic_private(&path, render_config(&config).as_bytes())?;
+
+    if json {
+        return print_json(&json!({ "path": path }));
+    }
+    println!("\n✓ 已创建配置文件: {}", path.display());
+    if config.smtp_enabled {
+        println!("可运行 `shu-net-keeper test-notify` 发送测试邮件");
+    }
+    println!("运行 `shu-net-keeper` 启动守护进程");
+    Ok(())
+}
+
+/// 交互式填写配置，验证失败时可以带着上次的输入重新填写
+/// 提示信息输出到标准错误，标准输出只用于结果（--json 时保持为合法的 JSON）
+fn wizard(path: &Path) -> Result<APPConfig> {
+    eprintln!("创建配置文件: {}", path.display());
+    eprintln!("直接回车使用方括号中的默认值\n");
+
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut config = APPConfig::default();
+
+    loop {
+        fill_config(&mut input, &mut config)?;
+
+        match config::validate_config(&config) {
+            Ok(_) => return Ok(config),
+            Err(e) => {
+                eprintln!("\n✗ {}", e);
+                if !confirm(&mut input, "是否重新填写？", true)? {
+                    return Err(e.into());
+                }
+                eprintln!();
+            }
+        }
+    }
+}
+
+fn fill_config(input: &mut impl BufRead, config: &mut APPConfig) -> Result<()> {
+    config.username = prompt(input, "学号", Some(&config.username))?;
+    config.password = prompt_password("校园网密码（输入时不显示）", !config.password.is_empty())?
+        .unwrap_or_else(|| config.password.clone());
+    config.interval = prompt_parse(input, "检查间隔（秒）", config.interval)?;
+
+    config.smtp_enabled = confirm(input, "是否启用登录邮件通知？", config.smtp_enabled)?;
+    if !config.smtp_enabled {
+        config.smtp = None;
+        return Ok(());
+    }
+
+    let previous = config.smtp.take().unwrap_or(SmtpConfig {
+        port: Some(465),
+        ..Default::default()
+    });
+    let server = prompt(
+        input,
+        "SMTP 服务器（如 smtp.qq.com）",
+        previous.server.as_deref(),
+    )?;
+    let port = prompt_parse(input, "SMTP 端口", previous.port.unwrap_or(465))?;
+    let sender = prompt(input, "发件人邮箱", previous.sender.as_deref())?;
+    let password = prompt_password(
+        "邮箱密码或授权码（输入时不显示）",
+        previous.password.is_some(),
+    )?
+    .or(previous.password);
+    let receiver = prompt(
+        input,
+        "收件人邮箱",
+        previous.receiver.as_deref().or(Some(sender.as_str())),
+    )?;
+
+    config.smtp = Some(SmtpConfig {
+        server: Some(server),
+        port: Some(port),
+        sender: Some(sender),
+        password,
+        receiver: Some(receiver),
+        ..Default::default()
+    });
+    Ok(())
+}
+
+/// 读取一行输入，直接回车时使用默认值
+fn prompt(input: &mut impl BufRead, label: &str, default: Option<&str>) -> Result<String> {
+    let default = default.filter(|d| !d.is_empty());
+    match default {
+        Some(default) => eprint!("{} [{}]: ", label, default),
+        None => eprint!("{}: ", label),
+    }
+    io::stderr().flush()?;
+
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Err(AppError::Other(
+            "输入已结束，已取消创建配置文件".to_string(),
+        ));
+    }
+
+    let value = line.trim();
+    Ok(match (value.is_empty(), default) {
+        (true, Some(default)) => default.to_string(),
+        _ => value.to_string(),
+    })
+}
+
+fn prompt_parse<T>(input: &mut impl BufRead, label: &str, default: T) -> Result<T>
+where
+    T: std::str::FromStr + ToString,
+{
+    loop {
+        let value = prompt(input, label, Some(&default.to_string()))?;
+        match value.parse() {
+            Ok(value) => return Ok(value),
+            Err(_) => eprintln!("✗ 请输入一个有效的数字"),
+        }
+    }
+}
+
+fn confirm(input: &mut impl BufRead, label: &str, default: bool) -> Result<bool> {
+    let hint = if default { "Y/n" } else { "y/N" };
+    loop {
+        let value = prompt(input, &format!("{} [{}]", label, hint), None)?;
+        match value.to_lowercase().as_str() {
+            "" => return Ok(default),
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => eprintln!("✗ 请输入 y 或 n"),
+        }
+    }
+}
+
+/// 读取不回显的密码；`keep_existing` 为 true 时直接回车返回 None（保留上次输入）
+fn prompt_password(label: &str, keep_existing: bool) -> Result<Option<String>> {
+    let label = if keep_existing {
+        format!("{}，直接回车保留上次输入: ", label)
+    } else {
+        format!("{}: ", label)
+    };
+    let password = rpassword::prompt_password(label)?;
+    Ok((!password.is_empty() || !keep_existing).then_some(password))
+}
+
+/// 生成带注释的配置文件内容
+fn render_config(config: &APPConfig) -> String {
+    let mut out = String::new();
+    out.push_str("# shu-net-keeper 配置文件（由 `shu-net-keeper init` 生成）\n\n");
+    out.push_str(&format!(
+        "# 配置格式版本，请勿修改\nversion = {}\n\n",
+        config.version
+    ));
+    out.push_str("# 校园网账号信息\n");
+    out.push_str(&format!("username = {}\n", quote(&config.username)));
+    out.push_str(&format!("password = {}\n\n", quote(&config.password)));
+    out.push_str(&format!(
+        "# 检查间隔（秒），默认 {} 秒\ninterval = {}\n\n",
+        DEFAULT_CHECK_INTERVAL, config.interval
+    ));
+    out.push_str("# 是否启用登录邮件通知\n");
+    out.push_str(&format!("smtp_enabled = {}\n", config.smtp_enabled));
+
+    if let Some(smtp) = config.smtp.as_ref().filter(|_| config.smtp_enabled) {
+        let field = |value: &Option<String>| quote(value.as_deref().unwrap_or_default());
+        out.push_str("\n[smtp]\n");
+        out.push_str(&format!(
+            "server = {}    # SMTP 服务器地址\n",
+            field(&smtp.server)
+        ));
+        out.push_str(&format!(
+            "port = {}    # 465 使用 SSL，其他端口使用 STARTTLS\n",
+            smtp.port.unwrap_or(465)
+        ));
+        out.push_str(&format!(
+            "sender = {}    # 发件人邮箱\n",
+            field(&smtp.sender)
+        ));
+        out.push_str(&format!(
+            "password = {}    # 邮箱密码或授权码\n",
+            field(&smtp.password)
+        ));
+        out.push_str(&format!(
+            "receiver = {}    # 收件人邮箱\n",
+            field(&smtp.receiver)
+        ));
+    }
+
+    out
+}
+
+/// 转换为 TOML 字符串字面量（处理引号和反斜杠等转义）
+fn quote(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shu_net_keeper::config::migration;
+
+    #[test]
+    fn test_render_config_round_trip() {
+        let config = APPConfig {
+            username: "12345678".to_string(),
+            password: r#"pa"ss\word"#.to_string(),
+            interval: 30,
+            smtp_enabled: true,
+            smtp: Some(SmtpConfig {
+                server: Some("smtp.qq.com".to_string()),
+                port: Some(465),
+                sender: Some("test@qq.com".to_string()),
+                password: Some("auth_code".to_string()),
+                receiver: Some("notify@example.com".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let parsed: APPConfig = toml::from_str(&render_config(&config)).unwrap();
+        let validated = config::validate_config(&parsed).unwrap();
+        assert_eq!(validated.password, r#"pa"ss\word"#);
+        assert_eq!(validated.interval, 30);
+        assert_eq!(validated.smtp.unwrap().receiver, "notify@example.com");
+        assert_eq!(parsed.version, migration::CONFIG_VERSION);
+
+        let (_, template_version) = migration::parse_toml(CONFIG_TEMPLATE).unwrap();
+        assert_eq!(template_version, migration::CONFIG_VERSION);
+    }
+
+    #[test]
+    fn test_prompt_default() {
+        let mut input = io::Cursor::new("\n30\n");
+        assert_eq!(
+            prompt(&mut input, "学号", Some("12345678")).unwrap(),
+            "12345678"
+        );
+        assert_eq!(prompt_parse(&mut input, "间隔", 10u64).unwrap(), 30);
+        assert!(prompt(&mut input, "学号", None).is_err());
+    }
+}
@@ -0,0 +1,49 @@
+use super::print_json;
+use clap::Subcommand;
+use serde_json::json;
+use shu_net_keeper::config::credentials::{CredentialStore, KEYRING_SERVICE, SystemKeyring};
+use shu_net_keeper::error::{AppError, Result};
+
+#[derive(Subcommand)]
+pub enum KeyringCommand {
+    /// 将密码保存到系统密钥环（交互式输入，不回显）
+    Set {
+        /// 条目名称，与配置中的 password_keyring 一致
+        name: String,
+    },
+    /// 从系统密钥环删除密码
+    Delete {
+        /// 条目名称
+        name: String,
+    },
+}
+
+/// 管理系统密钥环中保存的密码
+pub fn run(command: KeyringCommand, json: bool) -> Result<()> {
+    let store = SystemKeyring;
+    match command {
+        KeyringCommand::Set { name } => {
+            let secret = rpassword::prompt_password(format!("{} 的密码: ", name))?;
+            if secret.is_empty() {
+                return Err(AppError::Other("密码不能为空".to_string()));
+            }
+            store.set(&name, &secret)?;
+            if json {
+                return print_json(&json!({ "service": KEYRING_SERVICE, "name": name }));
+            }
+            println!("✓ 已保存到系统密钥环: {}/{}", KEYRING_SERVICE, name);
+            println!(
+                "在配置文件中使用 password_keyring = \"{}\" 引用该密码",
+                name
+            );
+        }
+        KeyringCommand::Delete { name } => {
+            store.delete(&name)?;
+            if json {
+                return print_json(&json!({ "service": KEYRING_SERVICE, "name": name }));
+            }
+            println!("✓ 已从系统密钥环删除: {}/{}", KEYRING_SERVICE, name);
+        }
+    }
+    Ok(())
+}
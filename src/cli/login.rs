@@ -0,0 +1,40 @@
+use super::ctl::print_outcome;
+use super::print_json;
+use serde_json::json;
+use shu_net_keeper::config;
+use shu_net_keeper::daemon::{self, Daemon, control};
+use shu_net_keeper::error::{AppError, LockError, Result};
+
+/// 立即登录一次
+pub fn login(json: bool) -> Result<()> {
+    let config = config::load_config()?;
+    let mut daemon = Daemon::new(config, &daemon::get_data_dir()).map_err(|e| match e {
+        AppError::Lock(LockError::AlreadyRunning { .. }) => AppError::Other(format!(
+            "{}\n守护进程正在运行时请改用 `shu-net-keeper ctl login`",
+            e
+        )),
+        e => e,
+    })?;
+
+    let result = daemon.login();
+    // 钩子在后台线程中执行，命令返回后进程即退出
+    daemon.wait_for_hooks();
+    let data = control::outcome_to_json(&result?);
+    if json {
+        return print_json(&data);
+    }
+    print_outcome(&data);
+    Ok(())
+}
+
+/// 注销当前在线会话
+pub fn logout(json: bool) -> Result<()> {
+    daemon::logout()?;
+
+    if json {
+        return print_json(&json!({ "logged_out": true }));
+    }
+    println!("✓ 已注销");
+    println!("提示: 如有守护进程在运行且未暂停，它会在下一次检查时重新登录（可先执行 ctl pause）");
+    Ok(())
+}
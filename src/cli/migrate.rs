@@ -0,0 +1,123 @@
+use super::print_json;
+use serde_json::json;
+use shu_net_keeper::config::{self, APPConfig, migration};
+use shu_net_keeper::error::{AppError, ConfigError, Result};
+use shu_net_keeper::fsutil;
+use std::fs;
+use std::path::PathBuf;
+
+/// 把配置文件升级到当前格式版本，原文件备份为 `*.bak`
+pub fn run(dry_run: bool, json: bool) -> Result<()> {
+    let (path, _) = config::find_config()?;
+    let content = fs::read_to_string(&path).map_err(|e| ConfigError::ReadFailed(e.to_string()))?;
+    let (config, from_version) = migration::parse_toml(&content)?;
+    let to_version = migration::CONFIG_VERSION;
+
+    if from_version == to_version {
+        if json {
+            return print_json(&json!({
+                "path": path,
+                "from_version": from_version,
+                "to_version": to_version,
+                "migrated": false,
+            }));
+        }
+        println!(
+            "✓ 配置文件已是最新格式（版本 {}）: {}",
+            to_version,
+            path.display()
+        );
+        return Ok(());
+    }
+
+    let migrated = render(&config, from_version)?;
+    if dry_run {
+        if json {
+            return print_json(&json!({
+                "path": path,
+                "from_version": from_version,
+                "to_version": to_version,
+                "migrated": false,
+                "content": migrated,
+            }));
+        }
+        print!("{}", migrated);
+        return Ok(());
+    }
+
+    let mut backup = path.clone().into_os_string();
+    backup.push(".bak");
+    let backup = PathBuf::from(backup);
+    fsutil::write_atomic_private(&backup, content.as_bytes())?;
+    fsutil::write_atomic_private(&path, migrated.as_bytes())?;
+
+    if json {
+        return print_json(&json!({
+            "path": path,
+            "from_version": from_version,
+            "to_version": to_version,
+            "migrated": true,
+            "backup": backup,
+        }));
+    }
+    println!(
+        "✓ 已将配置文件从版本 {} 升级到版本 {}: {}",
+        from_version,
+        to_version,
+        path.display()
+    );
+    println!(
+        "原文件已备份到 {}（注释未保留，请按需从备份中恢复）",
+        backup.display()
+    );
+    Ok(())
+}
+
+/// 按当前格式重新生成配置文件内容
+fn render(config: &APPConfig, from_version: u32) -> Result<String> {
+    let body = toml::to_string_pretty(config)
+        .map_err(|e| AppError::Other(format!("生成配置文件失败: {}", e)))?;
+    Ok(format!(
+        "# shu-net-keeper 配置文件（由 `shu-net-keeper migrate-config` 从版本 {} 升级）\n\n{}",
+        from_version, body
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_preserves_values() {
+        let legacy = r#"
+username = "12345678"
+password_env = "CAMPUS_PASSWORD"
+interval = 30
+
+[smtp]
+server = "smtp.qq.com"
+port = 465
+
+[hooks]
+on_ip_changed = "echo changed"
+"#;
+        let (config, from_version) = migration::parse_toml(legacy).unwrap();
+        let rendered = render(&config, from_version).unwrap();
+
+        let (reparsed, version) = migration::parse_toml(&rendered).unwrap();
+        assert_eq!(version, migration::CONFIG_VERSION);
+        assert_eq!(
+            reparsed.password_from.password_env.as_deref(),
+            Some("CAMPUS_PASSWORD")
+        );
+        assert_eq!(reparsed.interval, 30);
+        // 版本 0 缺少 smtp_enabled 即未启用，升级后保持不变
+        assert!(!reparsed.smtp_enabled);
+        assert_eq!(reparsed.smtp.unwrap().port, Some(465));
+        assert_eq!(
+            reparsed.hooks.unwrap().on_ip_changed.as_deref(),
+            Some("echo changed")
+        );
+        assert!(!rendered.contains("password ="));
+    }
+}
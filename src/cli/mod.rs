@@ -0,0 +1,135 @@
+pub mod config;
+pub mod ctl;
+pub mod diagnose;
+pub mod encrypt;
+pub mod init;
+pub mod keyring;
+pub mod login;
+pub mod migrate;
+pub mod once;
+pub mod stats;
+pub mod status;
+
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use shu_net_keeper::constants::STATS_RETENTION_DAYS;
+use shu_net_keeper::error::{AppError, Result};
+use std::path::PathBuf;
+
+/// 上海大学校园网自动登录守护程序
+#[derive(Parser)]
+#[command(name = "shu-net-keeper", version, about)]
+pub struct Cli {
+    /// 配置文件路径（也可通过环境变量 SHU_NET_KEEPER_CONFIG 指定，默认按顺序搜索）
+    #[arg(long, short, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// 日志级别或过滤表达式，如 debug、warn（默认读取 RUST_LOG，否则为 info）
+    #[arg(long, global = true, value_name = "LEVEL")]
+    pub log_level: Option<String>,
+
+    /// 以 JSON 格式输出结果
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Default)]
+pub enum Command {
+    /// 运行守护进程（默认）
+    #[default]
+    Run,
+
+    /// 立即登录一次（不检查当前是否在线）
+    Login,
+
+    /// 注销当前在线会话
+    Logout,
+
+    /// 查看当前在线状态
+    Status,
+
+    /// 交互式创建配置文件
+    Init {
+        /// 覆盖已存在的配置文件
+        #[arg(long)]
+        force: bool,
+
+        /// 不进行交互，只写入配置模板
+        #[arg(long)]
+        template: bool,
+    },
+
+    /// 逐步诊断登录流程（网关、重定向、登录参数解析、加密、在线信息接口）
+    Diagnose,
+
+    /// 检查配置文件是否有效
+    CheckConfig,
+
+    /// 将配置文件升级到当前格式版本（原文件备份为 .bak）
+    MigrateConfig {
+        /// 只输出升级后的内容，不修改文件
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// 输出配置文件的 JSON Schema（可用于编辑器补全或在生成配置后校验）
+    ConfigSchema,
+
+    /// 加密密码，输出可写入配置文件的 password_encrypted（首次使用时生成密钥文件）
+    EncryptPassword,
+
+    /// 发送一封测试邮件，验证 SMTP 配置
+    TestNotify,
+
+    /// 查看断网次数、可用率和平均恢复时间（MTTR）
+    Stats {
+        /// 按天统计的天数
+        #[arg(
+            long,
+            default_value_t = 7,
+            value_parser = clap::value_parser!(u32).range(1..=STATS_RETENTION_DAYS as i64)
+        )]
+        days: u32,
+
+        /// 按周统计的周数
+        #[arg(
+            long,
+            default_value_t = 4,
+            value_parser = clap::value_parser!(u32).range(1..=STATS_RETENTION_DAYS.div_ceil(7) as i64)
+        )]
+        weeks: u32,
+    },
+
+    /// 检查一次网络（必要时登录）后退出，结果通过退出码返回
+    ///
+    /// 退出码：0 已在线，10 本次完成登录，11 自动登录已暂停，
+    /// 20 登录被拒绝，30 无法访问门户，78 配置错误，1 其他错误
+    Once {
+        /// 以 Nagios 插件格式输出一行结果并使用 Nagios 退出码（0/1/2/3）
+        #[arg(long)]
+        nagios: bool,
+    },
+
+    /// 控制运行中的守护进程
+    Ctl {
+        #[command(subcommand)]
+        command: ctl::CtlCommand,
+    },
+
+    /// 管理系统密钥环中的密码（需要启用 keyring 功能编译）
+    Keyring {
+        #[command(subcommand)]
+        command: keyring::KeyringCommand,
+    },
+}
+
+/// 以格式化 JSON 输出到标准输出
+pub fn print_json(value: &impl Serialize) -> Result<()> {
+    let text = serde_json::to_string_pretty(value)
+        .map_err(|e| AppError::Other(format!("序列化输出失败: {}", e)))?;
+    println!("{}", text);
+    Ok(())
+}
@@ -0,0 +1,196 @@
+use serde::Serialize;
+use shu_net_keeper::daemon::{self, CheckOutcome, Daemon};
+use shu_net_keeper::error::{AppError, LoginError};
+use shu_net_keeper::{config, logger};
+use tracing::{info, warn};
+
+/// 单次检查的结果分类，决定进程退出码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnceStatus {
+    /// 已在线，无需登录
+    Online,
+    /// 本次完成了登录
+    LoggedIn,
+    /// 未登录，但自动登录已暂停
+    Paused,
+    /// 当前网络不匹配任何网络配置，未检查
+    Idle,
+    /// 门户拒绝登录（账号、密码错误等）
+    Rejected,
+    /// 无法访问校园网门户
+    Unreachable,
+    /// 配置文件缺失或无效
+    ConfigError,
+    /// 其他错误
+    Failed,
+}
+
+impl OnceStatus {
+    fn classify(result: &Result<CheckOutcome, AppError>) -> Self {
+        match result {
+            Ok(CheckOutcome::Online) => OnceStatus::Online,
+            Ok(CheckOutcome::LoggedIn { .. }) => OnceStatus::LoggedIn,
+            Ok(CheckOutcome::Paused) => OnceStatus::Paused,
+            Ok(CheckOutcome::Idle) => OnceStatus::Idle,
+            Err(AppError::Config(_) | AppError::Validation(_)) => OnceStatus::ConfigError,
+            Err(AppError::Login(LoginError::Authentication { .. })) => OnceStatus::Rejected,
+            Err(
+                AppError::Network(_)
+                | AppError::Login(LoginError::QueryString(_) | LoginError::Request(_)),
+            ) => OnceStatus::Unreachable,
+            Err(_) => OnceStatus::Failed,
+        }
+    }
+
+    /// 进程退出码（78 与 sysexits.h 的 EX_CONFIG 一致）
+    pub fn exit_code(self) -> i32 {
+        match self {
+            OnceStatus::Online => 0,
+            OnceStatus::LoggedIn => 10,
+            OnceStatus::Paused => 11,
+            OnceStatus::Idle => 12,
+            OnceStatus::Rejected => 20,
+            OnceStatus::Unreachable => 30,
+            OnceStatus::ConfigError => 78,
+            OnceStatus::Failed => 1,
+        }
+    }
+
+    /// Nagios 插件约定的退出码和状态名
+    fn nagios(self) -> (i32, &'static str) {
+        match self {
+            OnceStatus::Online | OnceStatus::LoggedIn | OnceStatus::Idle => (0, "OK"),
+            OnceStatus::Paused => (1, "WARNING"),
+            OnceStatus::Rejected | OnceStatus::Unreachable => (2, "CRITICAL"),
+            OnceStatus::ConfigError | OnceStatus::Failed => (3, "UNKNOWN"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct OnceReport {
+    status: OnceStatus,
+    exit_code: i32,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ip: Option<String>,
+}
+
+/// 执行一次检查（必要时登录）后返回退出码
+pub fn run(nagios: bool, json: bool, log_level: Option<&str>) -> i32 {
+    // JSON / Nagios 输出只占用标准输出，日志仅写入文件
+    let log_result = if nagios || json {
+        logger::init_file_only(log_level)
+    } else {
+        logger::init(log_level)
+    };
+    if let Err(e) = log_result {
+        eprintln!("✗ 日志系统初始化失败: {}", e);
+    }
+
+    let (result, ip) = check_once();
+    let status = OnceStatus::classify(&result);
+    let message = match &result {
+        Ok(CheckOutcome::Online) => "已在线".to_string(),
+        Ok(CheckOutcome::LoggedIn { ip_changed, .. }) if *ip_changed => {
+            "登录成功（IP 已变化）".to_string()
+        }
+        Ok(CheckOutcome::LoggedIn { .. }) => "登录成功".to_string(),
+        Ok(CheckOutcome::Paused) => "未登录，自动登录已暂停".to_string(),
+        Ok(CheckOutcome::Idle) => "当前网络不匹配任何网络配置，未检查".to_string(),
+        Err(e) => e.to_string(),
+    };
+    if let Ok(CheckOutcome::LoggedIn {
+        notification: Some(Err(e)),
+        ..
+    }) = &result
+    {
+        warn!("发送邮件通知失败: {}", e);
+    }
+
+    if nagios {
+        let (code, label) = status.nagios();
+        // Nagios 只读取第一行，消息中的换行替换为空格
+        let message = message.replace('\n', " ");
+        match &ip {
+            Some(ip) => println!("SHU-NET {} - {}，IP: {}", label, message, ip),
+            None => println!("SHU-NET {} - {}", label, message),
+        }
+        return code;
+    }
+
+    let exit_code = status.exit_code();
+    if json {
+        let report = OnceReport {
+            status,
+            exit_code,
+            message,
+            ip,
+        };
+        if let Err(e) = super::print_json(&report) {
+            eprintln!("✗ {}", e);
+        }
+    } else if result.is_ok() {
+        println!("✓ {}", message);
+    } else {
+        eprintln!("✗ {}", message);
+    }
+    exit_code
+}
+
+/// 加载配置并执行一次检查，同时返回检查后记录的 IP
+fn check_once() -> (Result<CheckOutcome, AppError>, Option<String>) {
+    let config = match config::load_config() {
+        Ok(config) => config,
+        Err(e) => return (Err(e.into()), None),
+    };
+    info!("单次检查，用户: {}", config.username);
+
+    let mut daemon = match Daemon::new(config, &daemon::get_data_dir()) {
+        Ok(daemon) => daemon,
+        Err(e) => return (Err(e), None),
+    };
+    let result = daemon.check();
+    daemon.wait_for_hooks();
+    let ip = match result {
+        Ok(CheckOutcome::Online | CheckOutcome::LoggedIn { .. }) => {
+            daemon.state().last_ip_address.clone()
+        }
+        _ => None,
+    };
+    (result, ip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shu_net_keeper::error::{ConfigError, NetworkError};
+
+    #[test]
+    fn test_classify_exit_codes() {
+        let code = |result: Result<CheckOutcome, AppError>| OnceStatus::classify(&result);
+
+        assert_eq!(code(Ok(CheckOutcome::Online)).exit_code(), 0);
+        assert_eq!(code(Ok(CheckOutcome::Paused)).exit_code(), 11);
+        assert_eq!(
+            code(Err(LoginError::Authentication {
+                status: 200,
+                message: "密码错误".to_string()
+            }
+            .into())),
+            OnceStatus::Rejected
+        );
+        assert_eq!(
+            code(Err(NetworkError::NotConnected("未连接".to_string()).into())),
+            OnceStatus::Unreachable
+        );
+        assert_eq!(
+            code(Err(ConfigError::ReadFailed("x".to_string()).into())).exit_code(),
+            78
+        );
+        assert_eq!(OnceStatus::Paused.nagios(), (1, "WARNING"));
+        assert_eq!(code(Ok(CheckOutcome::Idle)).exit_code(), 12);
+        assert_eq!(OnceStatus::Idle.nagios(), (0, "OK"));
+    }
+}
@@ -0,0 +1,89 @@
+use super::print_json;
+use shu_net_keeper::daemon::{self, OutageLog, StatsSummary};
+use shu_net_keeper::error::Result;
+
+/// 打印断网与可用性统计
+pub fn run(days: u32, weeks: u32, json: bool) -> Result<()> {
+    let stats_path = daemon::get_stats_path(&daemon::get_data_dir());
+    let log = OutageLog::load(&stats_path);
+    let now = chrono::Local::now();
+
+    let daily = log.daily(days, now);
+    let weekly = log.weekly(weeks, now);
+
+    if json {
+        return print_json(&serde_json::json!({
+            "current_outage": log.current(),
+            "daily": daily,
+            "weekly": weekly,
+        }));
+    }
+
+    println!("统计文件: {}", stats_path.display());
+    if let Some(outage) = log.current() {
+        println!(
+            "⚠️  当前处于断网状态，开始于 {}（{}）",
+            outage.start.format("%Y-%m-%d %H:%M:%S"),
+            outage.cause
+        );
+    }
+
+    println!("\n按天统计（最近 {} 天）", days);
+    print_table("日期", &daily);
+
+    println!("\n按周统计（最近 {} 周）", weeks);
+    print_table("周", &weekly);
+
+    Ok(())
+}
+
+const COLUMN_WIDTHS: [usize; 6] = [16, 10, 10, 12, 10, 10];
+
+fn print_table(label: &str, rows: &[StatsSummary]) {
+    print_row([label, "断网次数", "自动恢复", "断网时长", "可用率", "MTTR"]);
+    for row in rows {
+        print_row([
+            &row.label,
+            &row.outage_count.to_string(),
+            &row.auto_recovered_count.to_string(),
+            &format_duration(row.downtime_secs),
+            &format!("{:.2}%", row.availability * 100.0),
+            &row.mttr_secs
+                .map(format_duration)
+                .unwrap_or_else(|| "—".to_string()),
+        ]);
+    }
+}
+
+/// 按显示宽度对齐输出一行（第一列左对齐，其余右对齐）
+fn print_row(cells: [&str; 6]) {
+    let mut line = String::new();
+    for (i, (cell, width)) in cells.iter().zip(COLUMN_WIDTHS).enumerate() {
+        let padding = " ".repeat(width.saturating_sub(display_width(cell)));
+        if i == 0 {
+            line.push_str(cell);
+            line.push_str(&padding);
+        } else {
+            line.push_str(&padding);
+            line.push_str(cell);
+        }
+    }
+    println!("{}", line);
+}
+
+/// 终端显示宽度（中文等全角字符按 2 计算）
+fn display_width(s: &str) -> usize {
+    s.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum()
+}
+
+/// 将秒数格式化为易读的时长
+pub fn format_duration(secs: i64) -> String {
+    let (h, m, s) = (secs / 3600, secs % 3600 / 60, secs % 60);
+    if h > 0 {
+        format!("{}h{:02}m{:02}s", h, m, s)
+    } else if m > 0 {
+        format!("{}m{:02}s", m, s)
+    } else {
+        format!("{}s", s)
+    }
+}
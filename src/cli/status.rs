@@ -0,0 +1,82 @@
+use super::ctl::print_info;
+use super::print_json;
+use super::stats::format_duration;
+use chrono::Local;
+use serde_json::json;
+use shu_net_keeper::core::network;
+use shu_net_keeper::daemon::control::{self, ControlRequest};
+use shu_net_keeper::daemon::{self, DaemonState, StatusSnapshot};
+use shu_net_keeper::error::Result;
+
+/// 查询门户在线信息（以及运行中的守护进程）并输出当前在线状态
+/// 门户查询失败时仍然输出守护进程状态，最后返回门户的错误
+pub fn run(json: bool) -> Result<()> {
+    let portal = network::query_online_info();
+    let info = portal.as_ref().ok();
+    let logged_in = info.is_some_and(|info| info.user_ip.is_some());
+    let daemon = query_daemon();
+
+    // 本机记录的上次登录 IP 与门户一致时，用上次登录时间估算在线时长
+    let (last_ip, last_login_at) = match &daemon {
+        Some(status) => (status.ip.clone(), status.last_login_at),
+        None => {
+            let state = DaemonState::load(&daemon::get_state_path(&daemon::get_data_dir()));
+            (state.last_ip_address, state.last_login_at)
+        }
+    };
+    let online_secs = match (last_login_at, info) {
+        (Some(at), Some(info)) if logged_in && last_ip == info.user_ip => {
+            Some((Local::now() - at).num_seconds().max(0))
+        }
+        _ => None,
+    };
+
+    if json {
+        print_json(&json!({
+            "logged_in": info.map(|_| logged_in),
+            "online_info": info.filter(|_| logged_in),
+            "portal_items": info.map(|info| info.portal_items()),
+            "portal_error": portal.as_ref().err().map(|e| e.to_string()),
+            "online_secs": online_secs,
+            "daemon": daemon,
+        }))?;
+        return portal.map(|_| ()).map_err(Into::into);
+    }
+
+    match info {
+        Some(info) if logged_in => {
+            println!("✓ 已登录校园网");
+            print_info(info);
+            if let Some(secs) = online_secs {
+                println!("在线时长: {}（自本机上次登录）", format_duration(secs));
+            }
+        }
+        Some(_) => println!("✗ 未登录校园网"),
+        None => println!("? 无法查询门户在线信息"),
+    }
+
+    match &daemon {
+        Some(status) => {
+            let auto_login = if status.paused {
+                "自动登录已暂停"
+            } else {
+                "自动登录运行中"
+            };
+            println!(
+                "\n守护进程: 运行中（账号 {}，{}）",
+                status.username, auto_login
+            );
+            if let Some(error) = &status.last_error {
+                println!("最近错误: {}", error);
+            }
+        }
+        None => println!("\n守护进程: 未运行"),
+    }
+    portal.map(|_| ()).map_err(Into::into)
+}
+
+/// 通过控制接口查询守护进程状态，未运行或不支持时返回 None
+fn query_daemon() -> Option<StatusSnapshot> {
+    let response = control::send(&control::get_socket_path(), &ControlRequest::Status).ok()?;
+    serde_json::from_value(response.data?).ok()
+}
@@ -0,0 +1,191 @@
+//! 系统密钥环存储
+//!
+//! 配置中通过 `password_keyring = "名称"` 引用密钥环中的条目，密码本身不写入配置文件。
+//! 启用 `keyring` 功能编译时使用系统密钥环（Linux Secret Service、macOS 钥匙串、
+//! Windows 凭据管理器），否则所有操作都返回错误。
+
+use crate::config::types::APPConfig;
+use crate::error::{ConfigError, ConfigResult};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// 密钥环条目所属的服务名
+pub const KEYRING_SERVICE: &str = "shu-net-keeper";
+
+/// 编译时是否启用了系统密钥环支持
+pub const KEYRING_ENABLED: bool = cfg!(feature = "keyring");
+
+/// 按名称存取密码的凭据存储
+pub trait CredentialStore {
+    /// 读取密码，条目不存在时返回 None
+    fn get(&self, name: &str) -> ConfigResult<Option<String>>;
+
+    /// 写入（或覆盖）密码
+    fn set(&self, name: &str, secret: &str) -> ConfigResult<()>;
+
+    /// 删除条目，条目不存在时不报错
+    fn delete(&self, name: &str) -> ConfigResult<()>;
+}
+
+/// 系统密钥环
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemKeyring;
+
+#[cfg(feature = "keyring")]
+impl SystemKeyring {
+    fn entry(name: &str) -> ConfigResult<keyring::Entry> {
+        keyring::Entry::new(KEYRING_SERVICE, name).map_err(|e| keyring_error(name, e))
+    }
+}
+
+#[cfg(feature = "keyring")]
+fn keyring_error(name: &str, err: keyring::Error) -> ConfigError {
+    ConfigError::Secret(format!("访问密钥环条目 {} 失败: {}", name, err))
+}
+
+#[cfg(feature = "keyring")]
+impl CredentialStore for SystemKeyring {
+    fn get(&self, name: &str) -> ConfigResult<Option<String>> {
+        match Self::entry(name)?.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(keyring_error(name, e)),
+        }
+    }
+
+    fn set(&self, name: &str, secret: &str) -> ConfigResult<()> {
+        Self::entry(name)?
+            .set_password(secret)
+            .map_err(|e| keyring_error(name, e))
+    }
+
+    fn delete(&self, name: &str) -> ConfigResult<()> {
+        match Self::entry(name)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(keyring_error(name, e)),
+        }
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+impl CredentialStore for SystemKeyring {
+    fn get(&self, _name: &str) -> ConfigResult<Option<String>> {
+        Err(disabled())
+    }
+
+    fn set(&self, _name: &str, _secret: &str) -> ConfigResult<()> {
+        Err(disabled())
+    }
+
+    fn delete(&self, _name: &str) -> ConfigResult<()> {
+        Err(disabled())
+    }
+}
+
+#[cfg(not(feature = "keyring"))]
+fn disabled() -> ConfigError {
+    ConfigError::Secret("编译时未启用 keyring 功能，无法使用系统密钥环".to_string())
+}
+
+/// 内存中的凭据存储，用于测试或不需要持久化的场景
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    entries: Mutex<HashMap<String, String>>,
+}
+
+impl CredentialStore for MemoryStore {
+    fn get(&self, name: &str) -> ConfigResult<Option<String>> {
+        Ok(self.entries.lock().unwrap().get(name).cloned())
+    }
+
+    fn set(&self, name: &str, secret: &str) -> ConfigResult<()> {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(name.to_string(), secret.to_string());
+        Ok(())
+    }
+
+    fn delete(&self, name: &str) -> ConfigResult<()> {
+        self.entries.lock().unwrap().remove(name);
+        Ok(())
+    }
+}
+
+/// 将配置中的明文密码移入凭据存储，配置中改为引用条目 `{prefix}password`、`{prefix}smtp-password`
+pub fn move_passwords_to_store(
+    config: &mut APPConfig,
+    store: &dyn CredentialStore,
+    prefix: &str,
+) -> ConfigResult<()> {
+    if !config.password.is_empty() {
+        let name = format!("{}password", prefix);
+        store.set(&name, &config.password)?;
+        config.password.clear();
+        config.password_from.password_keyring = Some(name);
+    }
+
+    if let Some(smtp) = config.smtp.as_mut()
+        && let Some(password) = smtp.password.take().filter(|p| !p.is_empty())
+    {
+        let name = format!("{}smtp-password", prefix);
+        store.set(&name, &password)?;
+        smtp.password_from.password_keyring = Some(name);
+    }
+
+    Ok(())
+}
+
+/// 将配置中引用的密钥环条目读回明文密码（用于在界面中显示），条目不存在时密码留空
+pub fn restore_passwords_from_store(
+    config: &mut APPConfig,
+    store: &dyn CredentialStore,
+) -> ConfigResult<()> {
+    if let Some(name) = config.password_from.password_keyring.take() {
+        config.password = store.get(&name)?.unwrap_or_default();
+    }
+
+    if let Some(smtp) = config.smtp.as_mut()
+        && let Some(name) = smtp.password_from.password_keyring.take()
+    {
+        smtp.password = store.get(&name)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::SmtpConfig;
+
+    #[test]
+    fn test_move_and_restore_passwords() {
+        let store = MemoryStore::default();
+        let mut config = APPConfig {
+            username: "12345678".to_string(),
+            password: "campus-pass".to_string(),
+            smtp_enabled: true,
+            smtp: Some(SmtpConfig {
+                password: Some("auth-code".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        move_passwords_to_store(&mut config, &store, "gui/").unwrap();
+        let saved = serde_json::to_string(&config).unwrap();
+        assert!(!saved.contains("campus-pass") && !saved.contains("auth-code"));
+        assert!(saved.contains(r#""password_keyring":"gui/password""#));
+        assert_eq!(
+            store.get("gui/smtp-password").unwrap().as_deref(),
+            Some("auth-code")
+        );
+
+        let mut loaded: APPConfig = serde_json::from_str(&saved).unwrap();
+        restore_passwords_from_store(&mut loaded, &store).unwrap();
+        assert_eq!(loaded.password, "campus-pass");
+        assert_eq!(loaded.smtp.unwrap().password.as_deref(), Some("auth-code"));
+        assert_eq!(loaded.password_from.password_keyring, None);
+    }
+}
@@ -0,0 +1,143 @@
+//! 加密的密码字段
+//!
+//! `password_encrypted` 保存 XChaCha20-Poly1305 加密后的密码，格式为
+//! `v1:<base64(随机 nonce || 密文)>`。密钥（32 字节，base64 编码）来自环境变量
+//! `SHU_NET_KEEPER_KEY`，或单独的密钥文件，解密结果只保存在内存中。
+
+use crate::error::{ConfigError, ConfigResult};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 直接提供密钥（base64）的环境变量
+pub const KEY_ENV: &str = "SHU_NET_KEEPER_KEY";
+
+/// 指定密钥文件路径的环境变量
+pub const KEY_FILE_ENV: &str = "SHU_NET_KEEPER_KEY_FILE";
+
+/// 默认密钥文件名，位于配置文件所在目录
+pub const DEFAULT_KEY_FILE: &str = "secret.key";
+
+/// 加密格式版本前缀
+const PREFIX: &str = "v1:";
+
+const NONCE_LEN: usize = 24;
+
+/// 密码加密密钥
+pub struct SecretKey(Key);
+
+impl SecretKey {
+    /// 随机生成新密钥
+    pub fn generate() -> Self {
+        Self(XChaCha20Poly1305::generate_key(&mut OsRng))
+    }
+
+    /// 解析 base64 编码的密钥
+    pub fn parse(text: &str) -> ConfigResult<Self> {
+        let bytes = BASE64
+            .decode(text.trim())
+            .map_err(|e| ConfigError::Secret(format!("密钥不是有效的 base64: {}", e)))?;
+        if bytes.len() != 32 {
+            return Err(ConfigError::Secret(format!(
+                "密钥长度应为 32 字节，实际为 {} 字节",
+                bytes.len()
+            )));
+        }
+        Ok(Self(*Key::from_slice(&bytes)))
+    }
+
+    /// 编码为 base64，用于写入密钥文件
+    pub fn to_base64(&self) -> String {
+        BASE64.encode(self.0)
+    }
+
+    /// 加密密码，每次使用新的随机 nonce
+    pub fn encrypt(&self, plaintext: &str) -> String {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = XChaCha20Poly1305::new(&self.0)
+            .encrypt(&nonce, plaintext.as_bytes())
+            .expect("XChaCha20-Poly1305 加密不会失败");
+
+        let mut data = nonce.to_vec();
+        data.extend_from_slice(&ciphertext);
+        format!("{}{}", PREFIX, BASE64.encode(data))
+    }
+
+    /// 解密 `encrypt` 生成的值
+    pub fn decrypt(&self, value: &str) -> ConfigResult<String> {
+        let encoded = value
+            .trim()
+            .strip_prefix(PREFIX)
+            .ok_or_else(|| ConfigError::Secret(format!("加密密码应以 {} 开头", PREFIX)))?;
+        let data = BASE64
+            .decode(encoded)
+            .map_err(|e| ConfigError::Secret(format!("加密密码不是有效的 base64: {}", e)))?;
+        if data.len() <= NONCE_LEN {
+            return Err(ConfigError::Secret("加密密码长度不正确".to_string()));
+        }
+
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let plaintext = XChaCha20Poly1305::new(&self.0)
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| ConfigError::Secret("解密失败，密钥不匹配或加密密码已损坏".to_string()))?;
+        String::from_utf8(plaintext)
+            .map_err(|_| ConfigError::Secret("解密结果不是有效的 UTF-8".to_string()))
+    }
+}
+
+/// 密钥文件路径：优先使用 `SHU_NET_KEEPER_KEY_FILE`，否则为配置目录下的 secret.key
+pub fn key_file_path(base_dir: &Path) -> PathBuf {
+    match env::var(KEY_FILE_ENV) {
+        Ok(path) if !path.is_empty() => PathBuf::from(path),
+        _ => base_dir.join(DEFAULT_KEY_FILE),
+    }
+}
+
+/// 读取密钥：优先使用环境变量 `SHU_NET_KEEPER_KEY`，否则读取密钥文件
+pub fn load_key(base_dir: &Path) -> ConfigResult<SecretKey> {
+    if let Ok(key) = env::var(KEY_ENV)
+        && !key.is_empty()
+    {
+        return SecretKey::parse(&key)
+            .map_err(|e| ConfigError::Secret(format!("环境变量 {}: {}", KEY_ENV, e)));
+    }
+
+    let path = key_file_path(base_dir);
+    let content = fs::read_to_string(&path).map_err(|e| {
+        ConfigError::Secret(format!(
+            "读取密钥文件 {} 失败: {}（可设置环境变量 {} 或 {}）",
+            path.display(),
+            e,
+            KEY_ENV,
+            KEY_FILE_ENV
+        ))
+    })?;
+    SecretKey::parse(&content)
+        .map_err(|e| ConfigError::Secret(format!("密钥文件 {}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_round_trip() {
+        let key = SecretKey::generate();
+        let encrypted = key.encrypt("pa\"ss密码");
+        assert!(encrypted.starts_with(PREFIX));
+        assert_ne!(encrypted, key.encrypt("pa\"ss密码"));
+        assert_eq!(key.decrypt(&encrypted).unwrap(), "pa\"ss密码");
+
+        let parsed = SecretKey::parse(&key.to_base64()).unwrap();
+        assert_eq!(parsed.decrypt(&encrypted).unwrap(), "pa\"ss密码");
+
+        let other = SecretKey::generate();
+        assert!(other.decrypt(&encrypted).is_err());
+        assert!(key.decrypt("v1:AAAA").is_err());
+        assert!(SecretKey::parse("c2hvcnQ=").is_err());
+    }
+}
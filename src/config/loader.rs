@@ -1,33 +1,167 @@
+use crate::config::migration::{self, CONFIG_VERSION};
+use crate::config::secrets::resolve_secret_issues;
+use crate::config::source::TomlSource;
 use crate::config::types::*;
 use crate::config::validation::validate_config;
 use crate::error::{ConfigError, ConfigResult};
 use std::env;
+use std::fmt;
 use std::fs;
-use std::path::PathBuf;
-use tracing::{debug, error};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::{debug, error, info, warn};
 
-fn get_config_path() -> PathBuf {
-    match env::current_exe() {
-        Ok(exe_path) => match exe_path.parent() {
-            Some(exe_dir) => exe_dir.join("config.toml"),
-            None => PathBuf::from("config.toml"),
-        },
-        Err(_) => PathBuf::from("config.toml"),
+const CONFIG_FILE_NAME: &str = "config.toml";
+const CONFIG_DIR_NAME: &str = "shu-net-keeper";
+
+/// 指定配置文件路径的环境变量
+pub const CONFIG_PATH_ENV: &str = "SHU_NET_KEEPER_CONFIG";
+
+/// 命令行 `--config` 指定的配置文件路径
+static CONFIG_PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// 配置文件的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// 命令行 `--config`
+    Flag,
+    /// 环境变量 SHU_NET_KEEPER_CONFIG
+    Env,
+    /// 当前目录
+    CurrentDir,
+    /// `$XDG_CONFIG_HOME/shu-net-keeper/`（默认 `~/.config/shu-net-keeper/`）
+    UserConfig,
+    /// `/etc/shu-net-keeper/`
+    System,
+    /// 可执行文件所在目录
+    ExeDir,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            ConfigSource::Flag => "命令行参数 --config",
+            ConfigSource::Env => "环境变量 SHU_NET_KEEPER_CONFIG",
+            ConfigSource::CurrentDir => "当前目录",
+            ConfigSource::UserConfig => "用户配置目录",
+            ConfigSource::System => "系统配置目录",
+            ConfigSource::ExeDir => "程序所在目录",
+        };
+        f.write_str(text)
     }
 }
 
-pub fn load_config() -> ConfigResult<APPConfigValidated> {
-    let config_path = get_config_path();
+/// 指定配置文件路径（覆盖默认位置），只在启动时设置一次
+pub fn set_config_path(path: PathBuf) {
+    if CONFIG_PATH_OVERRIDE.set(path).is_err() {
+        debug!("配置文件路径已设置，忽略重复设置");
+    }
+}
+
+/// 显式指定的配置文件路径：命令行参数优先，其次是环境变量
+fn explicit_config_path() -> Option<(PathBuf, ConfigSource)> {
+    if let Some(path) = CONFIG_PATH_OVERRIDE.get() {
+        return Some((path.clone(), ConfigSource::Flag));
+    }
+    env::var_os(CONFIG_PATH_ENV)
+        .filter(|path| !path.is_empty())
+        .map(|path| (PathBuf::from(path), ConfigSource::Env))
+}
+
+/// 按搜索顺序列出候选路径：当前目录、用户配置目录、系统配置目录、程序所在目录
+fn search_paths() -> Vec<(PathBuf, ConfigSource)> {
+    let user_config_dir = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")));
+    let exe_dir = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(PathBuf::from));
+
+    candidates(
+        env::current_dir().ok(),
+        user_config_dir,
+        cfg!(unix).then(|| PathBuf::from("/etc")),
+        exe_dir,
+    )
+}
+
+fn candidates(
+    current_dir: Option<PathBuf>,
+    user_config_dir: Option<PathBuf>,
+    system_config_dir: Option<PathBuf>,
+    exe_dir: Option<PathBuf>,
+) -> Vec<(PathBuf, ConfigSource)> {
+    [
+        (current_dir, ConfigSource::CurrentDir),
+        (
+            user_config_dir.map(|dir| dir.join(CONFIG_DIR_NAME)),
+            ConfigSource::UserConfig,
+        ),
+        (
+            system_config_dir.map(|dir| dir.join(CONFIG_DIR_NAME)),
+            ConfigSource::System,
+        ),
+        (exe_dir, ConfigSource::ExeDir),
+    ]
+    .into_iter()
+    .filter_map(|(dir, source)| dir.map(|dir| (dir.join(CONFIG_FILE_NAME), source)))
+    .collect()
+}
+
+/// 查找要加载的配置文件
+/// 显式指定的路径（命令行参数、环境变量）不存在时直接报错，不再搜索其他位置
+pub fn find_config() -> ConfigResult<(PathBuf, ConfigSource)> {
+    if let Some((path, source)) = explicit_config_path() {
+        if !path.exists() {
+            error!("配置文件不存在: {}（{}）", path.display(), source);
+            return Err(ConfigError::FileNotFound {
+                path: path.display().to_string(),
+            });
+        }
+        return Ok((path, source));
+    }
 
-    debug!("配置文件路径: {}", config_path.display());
+    let candidates = search_paths();
+    if let Some(found) = candidates.iter().find(|(path, _)| path.is_file()) {
+        return Ok(found.clone());
+    }
+
+    let searched = candidates
+        .iter()
+        .map(|(path, _)| format!("  {}", path.display()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    error!("未找到配置文件，已搜索:\n{}", searched);
+    Err(ConfigError::NotFound { searched })
+}
 
-    if !config_path.exists() {
-        error!("配置文件不存在: {}", config_path.display());
-        return Err(ConfigError::FileNotFound {
-            path: config_path.display().to_string(),
-        });
+/// 获取配置文件路径：显式指定的路径，或搜索到的第一个配置文件；
+/// 都没有时为用户配置目录下的 shu-net-keeper/config.toml（`init` 默认在此创建），
+/// 无法确定用户配置目录时为程序所在目录下的 config.toml
+pub fn get_config_path() -> PathBuf {
+    if let Some((path, _)) = explicit_config_path() {
+        return path;
     }
 
+    let candidates = search_paths();
+    candidates
+        .iter()
+        .find(|(path, _)| path.is_file())
+        .or_else(|| {
+            candidates
+                .iter()
+                .find(|(_, source)| *source == ConfigSource::UserConfig)
+        })
+        .or_else(|| candidates.last())
+        .map(|(path, _)| path.clone())
+        .unwrap_or_else(|| PathBuf::from(CONFIG_FILE_NAME))
+}
+
+pub fn load_config() -> ConfigResult<APPConfigValidated> {
+    let (config_path, source) = find_config()?;
+    info!("使用配置文件: {}（{}）", config_path.display(), source);
+
     debug!("正在读取配置文件...");
     let content = fs::read_to_string(&config_path).map_err(|e| {
         error!("读取配置文件失败: {}", e);
@@ -35,10 +169,63 @@ pub fn load_config() -> ConfigResult<APPConfigValidated> {
     })?;
 
     debug!("正在解析配置文件...");
-    let config: APPConfig = toml::from_str(&content).map_err(|e| {
-        error!("解析配置文件失败: {}", e);
-        ConfigError::ParseFailed(e.to_string())
-    })?;
+    let (mut config, from_version) =
+        migration::parse_toml(&content).inspect_err(|e| error!("{}", e))?;
+    if from_version < CONFIG_VERSION {
+        warn!(
+            "配置文件格式版本为 {}，已在内存中升级到版本 {}，可运行 `shu-net-keeper migrate-config` 更新配置文件",
+            from_version, CONFIG_VERSION
+        );
+    }
 
-    validate_config(&config)
+    // 密码读取失败与其他问题一起报告
+    let base_dir = config_path.parent().unwrap_or(Path::new("."));
+    let mut issues = resolve_secret_issues(&mut config, base_dir);
+    for issue in &issues {
+        error!("读取密码失败: {}", issue);
+    }
+
+    match validate_config(&config) {
+        Ok(validated) if issues.is_empty() => return Ok(validated),
+        Ok(_) => {}
+        Err(ConfigError::Invalid { issues: invalid }) => {
+            // 密码读取失败时，验证阶段的“密码为空”是同一个问题
+            let invalid: Vec<_> = invalid
+                .into_iter()
+                .filter(|issue| !issues.iter().any(|secret| secret.field == issue.field))
+                .collect();
+            issues.extend(invalid);
+        }
+        Err(err) => return Err(err),
+    }
+
+    TomlSource::new(&content).annotate(&mut issues);
+    Err(ConfigError::Invalid { issues })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_order() {
+        let paths = candidates(
+            Some(PathBuf::from("/work")),
+            Some(PathBuf::from("/home/u/.config")),
+            None,
+            Some(PathBuf::from("/opt/bin")),
+        );
+
+        assert_eq!(
+            paths,
+            vec![
+                (PathBuf::from("/work/config.toml"), ConfigSource::CurrentDir),
+                (
+                    PathBuf::from("/home/u/.config/shu-net-keeper/config.toml"),
+                    ConfigSource::UserConfig
+                ),
+                (PathBuf::from("/opt/bin/config.toml"), ConfigSource::ExeDir),
+            ]
+        );
+    }
 }
@@ -0,0 +1,128 @@
+//! 配置格式版本与迁移
+//!
+//! 配置中的 `version` 记录格式版本，缺省视为 0（引入版本号之前的配置）。
+//! 加载时按迁移链逐级升级到当前版本，TOML 配置文件与 GUI 的 settings.json 共用同一条迁移链。
+
+use crate::config::source::TomlSource;
+use crate::config::types::APPConfig;
+use crate::error::{ConfigError, ConfigResult};
+use serde_json::{Map, Value};
+
+/// 当前配置格式版本
+pub const CONFIG_VERSION: u32 = 1;
+
+type Migration = fn(&mut Map<String, Value>);
+
+/// 迁移链：第 i 项把版本 i 的配置升级到版本 i + 1
+const MIGRATIONS: [Migration; CONFIG_VERSION as usize] = [v0_to_v1];
+
+/// v0 → v1：只引入 `version` 字段，其余字段格式不变
+/// （缺少 smtp_enabled 时默认值为 false，与早期版本一致，升级后不会开始发送邮件）
+fn v0_to_v1(_config: &mut Map<String, Value>) {}
+
+/// 把配置升级到当前版本，返回升级前的版本
+pub fn migrate(value: &mut Value) -> ConfigResult<u32> {
+    let config = value
+        .as_object_mut()
+        .ok_or_else(|| ConfigError::ParseFailed("配置必须是一个表".to_string()))?;
+
+    let from = match config.get("version") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| {
+                ConfigError::ParseFailed(format!("version 必须是非负整数，当前为 {}", version))
+            })?,
+    };
+    if from > CONFIG_VERSION {
+        return Err(ConfigError::UnsupportedVersion {
+            found: from,
+            supported: CONFIG_VERSION,
+        });
+    }
+
+    for migration in &MIGRATIONS[from as usize..] {
+        migration(config);
+    }
+    config.insert("version".to_string(), Value::from(CONFIG_VERSION));
+    Ok(from)
+}
+
+/// 解析 TOML 配置并升级到当前版本，返回配置和升级前的版本
+pub fn parse_toml(content: &str) -> ConfigResult<(APPConfig, u32)> {
+    let table: toml::Table = toml::from_str(content).map_err(|e| parse_error(content, &e))?;
+    let mut value =
+        serde_json::to_value(table).map_err(|e| ConfigError::ParseFailed(e.to_string()))?;
+    let from = migrate(&mut value)?;
+
+    // 无需迁移时直接从原文解析，错误信息保留行列位置
+    let config = if from == CONFIG_VERSION {
+        toml::from_str(content).map_err(|e| parse_error(content, &e))?
+    } else {
+        // 迁移后的配置失去了位置信息，出错时尽量从原文解析以定位错误
+        serde_json::from_value(value).map_err(|e| match toml::from_str::<APPConfig>(content) {
+            Err(err) => parse_error(content, &err),
+            Ok(_) => ConfigError::ParseFailed(e.to_string()),
+        })?
+    };
+    Ok((config, from))
+}
+
+/// 解析 JSON 配置（GUI 的 settings.json）并升级到当前版本，返回配置和升级前的版本
+pub fn parse_json(mut value: Value) -> ConfigResult<(APPConfig, u32)> {
+    let from = migrate(&mut value)?;
+    let config =
+        serde_json::from_value(value).map_err(|e| ConfigError::ParseFailed(e.to_string()))?;
+    Ok((config, from))
+}
+
+/// TOML 解析错误带上行列位置，语法错误一次性全部报告
+fn parse_error(content: &str, err: &toml::de::Error) -> ConfigError {
+    ConfigError::Invalid {
+        issues: TomlSource::new(content).parse_issues(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_migrate_legacy_toml() {
+        let legacy = r#"
+username = "12345678"
+password = "testpass"
+
+[smtp]
+server = "smtp.qq.com"
+"#;
+        let (config, from) = parse_toml(legacy).unwrap();
+        assert_eq!(from, 0);
+        assert_eq!(config.version, CONFIG_VERSION);
+        // 早期版本中缺少 smtp_enabled 即未启用，有 [smtp] 也一样
+        assert!(!config.smtp_enabled);
+        assert_eq!(config.smtp.unwrap().server.as_deref(), Some("smtp.qq.com"));
+
+        let explicit = "version = 0\nusername = \"12345678\"\nsmtp_enabled = true\n[smtp]\n";
+        assert!(parse_toml(explicit).unwrap().0.smtp_enabled);
+    }
+
+    #[test]
+    fn test_migrate_json_and_versions() {
+        let (config, from) = parse_json(json!({ "username": "12345678", "smtp": null })).unwrap();
+        assert_eq!(from, 0);
+        assert!(!config.smtp_enabled);
+
+        let current = json!({ "version": CONFIG_VERSION, "username": "12345678" });
+        assert_eq!(parse_json(current).unwrap().1, CONFIG_VERSION);
+
+        let future = json!({ "version": CONFIG_VERSION + 1, "username": "12345678" });
+        assert!(matches!(
+            parse_json(future),
+            Err(ConfigError::UnsupportedVersion { .. })
+        ));
+        assert!(parse_json(json!({ "version": "1" })).is_err());
+    }
+}
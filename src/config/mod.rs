@@ -1,17 +1,31 @@
+pub mod credentials;
+pub mod encryption;
 mod loader;
+pub mod migration;
+mod schema;
+mod secrets;
+mod source;
 mod types;
 mod validation;
 
-pub use loader::load_config;
+pub use loader::{
+    CONFIG_PATH_ENV, ConfigSource, find_config, get_config_path, load_config, set_config_path,
+};
+pub use schema::config_schema;
+pub use secrets::{PASSWORD_ENV, SMTP_PASSWORD_ENV, resolve_secrets, resolve_secrets_with};
 #[allow(unused_imports)]
-pub use types::{APPConfig, APPConfigValidated, SmtpConfig, SmtpConfigValidated};
+pub use types::{
+    APPConfig, APPConfigValidated, AccountRule, HooksConfig, HooksConfigValidated, HttpConfig,
+    HttpConfigValidated, ProfileConfig, ProfileValidated, SecretSources, SmtpConfig,
+    SmtpConfigValidated,
+};
 #[allow(unused_imports)]
-pub use validation::validate_config;
+pub use validation::{check_username, validate_config};
 
 #[cfg(test)]
 mod tests {
     use super::types::*;
-    use super::validation::validate_config;
+    use super::validation::{check_username, validate_config};
 
     // ============ SmtpConfig 验证测试 ============
 
@@ -23,6 +37,7 @@ mod tests {
             sender: Some("test@qq.com".to_string()),
             password: Some("auth_code".to_string()),
             receiver: Some("notify@example.com".to_string()),
+            ..Default::default()
         };
 
         let config = APPConfig {
@@ -31,6 +46,7 @@ mod tests {
             interval: 10,
             smtp_enabled: true,
             smtp: Some(smtp),
+            ..Default::default()
         };
 
         assert!(validate_config(&config).is_ok());
@@ -44,6 +60,7 @@ mod tests {
             sender: Some("not_an_email".to_string()), // 无效
             password: Some("auth".to_string()),
             receiver: Some("notify@example.com".to_string()),
+            ..Default::default()
         };
 
         let config = APPConfig {
@@ -52,6 +69,7 @@ mod tests {
             interval: 10,
             smtp_enabled: true,
             smtp: Some(smtp),
+            ..Default::default()
         };
 
         assert!(validate_config(&config).is_err());
@@ -65,6 +83,7 @@ mod tests {
             sender: Some("test@qq.com".to_string()),
             password: Some("auth".to_string()),
             receiver: Some("test@example.com".to_string()),
+            ..Default::default()
         };
 
         let config = APPConfig {
@@ -73,6 +92,7 @@ mod tests {
             interval: 10,
             smtp_enabled: true,
             smtp: Some(smtp),
+            ..Default::default()
         };
 
         assert!(validate_config(&config).is_err());
@@ -86,6 +106,7 @@ mod tests {
             sender: Some("test@qq.com".to_string()),
             password: Some("auth".to_string()),
             receiver: Some("notify@example.com".to_string()),
+            ..Default::default()
         };
 
         let config = APPConfig {
@@ -94,6 +115,7 @@ mod tests {
             interval: 10,
             smtp_enabled: true,
             smtp: Some(smtp),
+            ..Default::default()
         };
 
         assert!(validate_config(&config).is_err());
@@ -109,6 +131,7 @@ mod tests {
             interval: 10,
             smtp_enabled: false,
             smtp: None,
+            ..Default::default()
         };
 
         let validated = validate_config(&config).unwrap();
@@ -129,7 +152,9 @@ mod tests {
                 sender: Some("test@qq.com".to_string()),
                 password: Some("auth".to_string()),
                 receiver: Some("notify@example.com".to_string()),
+                ..Default::default()
             }),
+            ..Default::default()
         };
 
         let validated = validate_config(&config).unwrap();
@@ -149,6 +174,7 @@ mod tests {
             interval: 10,
             smtp_enabled: true, // 启用了
             smtp: None,         // 但没配置
+            ..Default::default()
         };
 
         assert!(validate_config(&config).is_err());
@@ -162,6 +188,7 @@ mod tests {
             interval: 10,
             smtp_enabled: false,
             smtp: None,
+            ..Default::default()
         };
 
         assert!(validate_config(&config).is_err());
@@ -175,11 +202,81 @@ mod tests {
             interval: 10,
             smtp_enabled: false,
             smtp: None,
+            ..Default::default()
         };
 
         assert!(validate_config(&config).is_err());
     }
 
+    #[test]
+    fn test_config_account_rules() {
+        let mut config = APPConfig {
+            username: "T2024001".to_string(),
+            password: "testpass".to_string(),
+            ..Default::default()
+        };
+        assert!(validate_config(&config).is_err());
+
+        config.account = AccountRule::Length {
+            min_length: 6,
+            max_length: 12,
+            digits_only: false,
+        };
+        assert!(validate_config(&config).is_ok());
+
+        config.account = AccountRule::Regex {
+            pattern: "[A-Z]\\d{7}".to_string(),
+        };
+        assert!(validate_config(&config).is_ok());
+        config.username = "T2024001x".to_string();
+        assert!(validate_config(&config).is_err());
+
+        config.account = AccountRule::Unrestricted;
+        assert!(validate_config(&config).is_ok());
+
+        // 规则本身无效
+        config.account = AccountRule::Regex {
+            pattern: "(".to_string(),
+        };
+        assert!(validate_config(&config).is_err());
+
+        let parsed: APPConfig = toml::from_str(
+            "username = \"guest\"\n[account]\nrule = \"length\"\nmin_length = 4\nmax_length = 10\n",
+        )
+        .unwrap();
+        assert!(check_username(&parsed.username, &parsed.account).is_ok());
+        assert!(APPConfig::default().account.is_default());
+    }
+
+    #[test]
+    fn test_config_profiles() {
+        let content = r#"username = "12345678"
+password = "testpass"
+
+[[profiles]]
+name = "宿舍"
+interface = ["eth0"]
+subnet = ["10.10.0.0/16"]
+
+[[profiles]]
+name = "校园 Wi-Fi"
+ssid = ["Shu(ForAll)"]
+interval = 30
+"#;
+        let mut config: APPConfig = toml::from_str(content).unwrap();
+        let validated = validate_config(&config).unwrap();
+        assert_eq!(validated.profiles.len(), 2);
+        assert_eq!(validated.profiles[1].interval, Some(30));
+
+        config.profiles[1].name = "宿舍".to_string();
+        config.profiles[1].gateway = vec!["10.10.0.x".to_string()];
+        let Err(crate::error::ConfigError::Invalid { issues }) = validate_config(&config) else {
+            panic!("重复名称和无效网关应当报错");
+        };
+        let fields: Vec<_> = issues.iter().filter_map(|i| i.field.as_deref()).collect();
+        assert_eq!(fields, ["profiles[1].name", "profiles[1].gateway"]);
+    }
+
     #[test]
     fn test_config_empty_password() {
         let config = APPConfig {
@@ -188,8 +285,29 @@ mod tests {
             interval: 10,
             smtp_enabled: false,
             smtp: None,
+            ..Default::default()
         };
 
         assert!(validate_config(&config).is_err());
     }
+
+    #[test]
+    fn test_config_http_bind() {
+        let mut config = APPConfig {
+            username: "12345678".to_string(),
+            password: "testpass".to_string(),
+            http: Some(HttpConfig {
+                bind: "0.0.0.0:9870".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let validated = validate_config(&config).unwrap();
+        assert_eq!(validated.http.unwrap().bind.port(), 9870);
+
+        config.http = Some(HttpConfig {
+            bind: "localhost".to_string(),
+        });
+        assert!(validate_config(&config).is_err());
+    }
 }
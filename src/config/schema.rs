@@ -0,0 +1,31 @@
+//! 配置文件的 JSON Schema
+//!
+//! 由 `APPConfig` 的类型定义和 `#[schemars]` 标注生成，约束与 config::validation 保持一致；
+//! 账号格式等取决于其他字段的规则只写在描述中，仍以 `check-config` 的结果为准。
+
+use crate::config::types::APPConfig;
+use serde_json::Value;
+
+/// 生成配置的 JSON Schema
+pub fn config_schema() -> Value {
+    schemars::schema_for!(APPConfig).to_value()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_constraints() {
+        let schema = config_schema();
+        let properties = &schema["properties"];
+        assert_eq!(properties["username"]["minLength"], 1);
+        assert_eq!(properties["interval"]["default"], 10);
+        assert!(properties["password_env"].is_object());
+
+        let smtp = &schema["$defs"]["SmtpConfig"]["properties"];
+        assert_eq!(smtp["port"]["minimum"], 1);
+        assert_eq!(smtp["sender"]["format"], "email");
+        assert_eq!(schema["then"]["required"][0], "smtp");
+    }
+}
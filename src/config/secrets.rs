@@ -0,0 +1,345 @@
+//! 密码来源解析
+//!
+//! 密码可以直接写在配置文件中，也可以加密后写入，或来自环境变量、文件、命令输出、系统密钥环。
+//! 在 `config::loader` 中于验证之前解析，解析结果只保存在内存中。
+
+use crate::config::credentials::{CredentialStore, SystemKeyring};
+use crate::config::encryption;
+use crate::config::types::{APPConfig, SecretSources};
+use crate::core::hooks::shell_command;
+use crate::error::{ConfigError, ConfigIssue, ConfigResult};
+use std::env;
+use std::fs;
+use std::path::Path;
+use tracing::debug;
+
+/// 配置中未指定校园网密码时读取的环境变量
+pub const PASSWORD_ENV: &str = "SHU_NET_KEEPER_PASSWORD";
+
+/// 配置中未指定 SMTP 密码时读取的环境变量
+pub const SMTP_PASSWORD_ENV: &str = "SHU_NET_KEEPER_SMTP_PASSWORD";
+
+/// 解析校园网密码和 SMTP 密码，结果写回 `config`
+/// `base_dir` 为配置文件所在目录，用于解析相对的 password_file 路径
+pub fn resolve_secrets(config: &mut APPConfig, base_dir: &Path) -> ConfigResult<()> {
+    resolve_secrets_with(config, base_dir, &SystemKeyring)
+}
+
+/// 同 [`resolve_secrets`]，password_keyring 从指定的凭据存储读取
+pub fn resolve_secrets_with(
+    config: &mut APPConfig,
+    base_dir: &Path,
+    store: &dyn CredentialStore,
+) -> ConfigResult<()> {
+    match secret_issues(config, base_dir, store).into_iter().next() {
+        Some(issue) => Err(ConfigError::Secret(issue.to_string())),
+        None => Ok(()),
+    }
+}
+
+/// 解析全部密码，每个无法读取的密码记为一处问题（字段为 `password` 或 `smtp.password`），
+/// 加载配置时与验证问题一起报告
+pub(crate) fn resolve_secret_issues(config: &mut APPConfig, base_dir: &Path) -> Vec<ConfigIssue> {
+    secret_issues(config, base_dir, &SystemKeyring)
+}
+
+fn secret_issues(
+    config: &mut APPConfig,
+    base_dir: &Path,
+    store: &dyn CredentialStore,
+) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+
+    let inline = Some(config.password.as_str()).filter(|p| !p.is_empty());
+    match resolve(
+        "password",
+        inline,
+        &config.password_from,
+        PASSWORD_ENV,
+        base_dir,
+        store,
+    ) {
+        Ok(Some(password)) => config.password = password,
+        Ok(None) => {}
+        Err(e) => issues.push(ConfigIssue::new("password", secret_message(e))),
+    }
+
+    if config.smtp_enabled
+        && let Some(smtp) = config.smtp.as_mut()
+    {
+        let inline = smtp.password.as_deref().filter(|p| !p.is_empty());
+        match resolve(
+            "smtp.password",
+            inline,
+            &smtp.password_from,
+            SMTP_PASSWORD_ENV,
+            base_dir,
+            store,
+        ) {
+            Ok(Some(password)) => smtp.password = Some(password),
+            Ok(None) => {}
+            Err(e) => issues.push(ConfigIssue::new("smtp.password", secret_message(e))),
+        }
+    }
+
+    issues
+}
+
+/// 问题中已经包含字段名，去掉 “读取密码失败” 前缀
+fn secret_message(err: ConfigError) -> String {
+    match err {
+        ConfigError::Secret(message) => message,
+        err => err.to_string(),
+    }
+}
+
+/// 解析单个密码：配置中最多只能指定一个来源，都未指定时读取 `fallback_env`
+fn resolve(
+    field: &str,
+    inline: Option<&str>,
+    sources: &SecretSources,
+    fallback_env: &str,
+    base_dir: &Path,
+    store: &dyn CredentialStore,
+) -> ConfigResult<Option<String>> {
+    let configured = [
+        inline.is_some(),
+        sources.password_encrypted.is_some(),
+        sources.password_env.is_some(),
+        sources.password_file.is_some(),
+        sources.password_command.is_some(),
+        sources.password_keyring.is_some(),
+    ]
+    .into_iter()
+    .filter(|&set| set)
+    .count();
+    if configured > 1 {
+        return Err(ConfigError::Secret(
+            "只能通过 password、password_encrypted、password_env、password_file、password_command、password_keyring 中的一个提供".to_string(),
+        ));
+    }
+
+    if let Some(password) = inline {
+        return Ok(Some(password.to_string()));
+    }
+
+    if let Some(encrypted) = &sources.password_encrypted {
+        debug!("{}: 解密 password_encrypted", field);
+        let key = encryption::load_key(base_dir)?;
+        let password = key
+            .decrypt(encrypted)
+            .map_err(|e| ConfigError::Secret(secret_message(e)))?;
+        return non_empty(&password, "解密后的密码");
+    }
+
+    if let Some(name) = &sources.password_env {
+        debug!("{}: 从环境变量 {} 读取", field, name);
+        return match env::var(name) {
+            Ok(value) if !value.is_empty() => Ok(Some(value)),
+            _ => Err(ConfigError::Secret(format!(
+                "环境变量 {} 未设置或为空",
+                name
+            ))),
+        };
+    }
+
+    if let Some(file) = &sources.password_file {
+        let path = base_dir.join(file);
+        debug!("{}: 从文件 {} 读取", field, path.display());
+        let content = fs::read_to_string(&path).map_err(|e| {
+            ConfigError::Secret(format!("读取密码文件 {} 失败: {}", path.display(), e))
+        })?;
+        return non_empty(
+            first_line(&content),
+            &format!("密码文件 {}", path.display()),
+        );
+    }
+
+    if let Some(command) = &sources.password_command {
+        debug!("{}: 执行密码命令", field);
+        let output = shell_command(command)
+            .output()
+            .map_err(|e| ConfigError::Secret(format!("执行密码命令失败: {}", e)))?;
+        if !output.status.success() {
+            return Err(ConfigError::Secret(format!(
+                "密码命令退出状态 {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        return non_empty(first_line(&stdout), "密码命令的输出");
+    }
+
+    if let Some(name) = &sources.password_keyring {
+        debug!("{}: 从系统密钥环读取条目 {}", field, name);
+        return match store.get(name)? {
+            Some(secret) => non_empty(&secret, &format!("密钥环条目 {}", name)),
+            None => Err(ConfigError::Secret(format!(
+                "密钥环中不存在条目 {}，请先运行 `shu-net-keeper keyring set {}`",
+                name, name
+            ))),
+        };
+    }
+
+    match env::var(fallback_env) {
+        Ok(value) if !value.is_empty() => {
+            debug!("{}: 从环境变量 {} 读取", field, fallback_env);
+            Ok(Some(value))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// 取第一行并去掉行尾换行（文件和命令输出通常以换行结尾）
+fn first_line(content: &str) -> &str {
+    content.lines().next().unwrap_or_default()
+}
+
+fn non_empty(value: &str, source: &str) -> ConfigResult<Option<String>> {
+    if value.is_empty() {
+        return Err(ConfigError::Secret(format!("{}为空", source)));
+    }
+    Ok(Some(value.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::credentials::MemoryStore;
+
+    fn sources(file: Option<&str>, command: Option<&str>) -> SecretSources {
+        SecretSources {
+            password_file: file.map(String::from),
+            password_command: command.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_resolve_from_file() {
+        let path = "test_secret_password.txt";
+        fs::write(path, "s3cret\n").unwrap();
+
+        let resolved = resolve(
+            "password",
+            None,
+            &sources(Some(path), None),
+            "",
+            Path::new("."),
+            &MemoryStore::default(),
+        );
+        assert_eq!(resolved.unwrap().as_deref(), Some("s3cret"));
+
+        fs::remove_file(path).unwrap();
+        let missing = resolve(
+            "password",
+            None,
+            &sources(Some(path), None),
+            "",
+            Path::new("."),
+            &MemoryStore::default(),
+        );
+        assert!(matches!(missing, Err(ConfigError::Secret(_))));
+    }
+
+    #[test]
+    fn test_resolve_conflicting_sources() {
+        let result = resolve(
+            "password",
+            Some("inline"),
+            &sources(Some("file"), None),
+            "",
+            Path::new("."),
+            &MemoryStore::default(),
+        );
+        assert!(matches!(result, Err(ConfigError::Secret(_))));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_from_command() {
+        let resolved = resolve(
+            "password",
+            None,
+            &sources(None, Some("printf 'from-command\\nignored'")),
+            "",
+            Path::new("."),
+            &MemoryStore::default(),
+        );
+        assert_eq!(resolved.unwrap().as_deref(), Some("from-command"));
+
+        let failed = resolve(
+            "password",
+            None,
+            &sources(None, Some("exit 3")),
+            "",
+            Path::new("."),
+            &MemoryStore::default(),
+        );
+        assert!(matches!(failed, Err(ConfigError::Secret(_))));
+    }
+
+    #[test]
+    fn test_resolve_from_keyring() {
+        let store = MemoryStore::default();
+        let sources = SecretSources {
+            password_keyring: Some("campus".to_string()),
+            ..Default::default()
+        };
+
+        let missing = resolve("password", None, &sources, "", Path::new("."), &store);
+        assert!(matches!(missing, Err(ConfigError::Secret(_))));
+
+        store.set("campus", "from-keyring").unwrap();
+        let mut config = APPConfig {
+            username: "12345678".to_string(),
+            password_from: sources,
+            ..Default::default()
+        };
+        resolve_secrets_with(&mut config, Path::new("."), &store).unwrap();
+        assert_eq!(config.password, "from-keyring");
+    }
+
+    #[test]
+    fn test_secret_issues_are_collected() {
+        let mut config = APPConfig {
+            username: "12345678".to_string(),
+            password_from: SecretSources {
+                password_env: Some("SHU_NET_KEEPER_TEST_UNSET".to_string()),
+                ..Default::default()
+            },
+            smtp_enabled: true,
+            smtp: Some(crate::config::SmtpConfig {
+                password: Some("inline".to_string()),
+                password_from: sources(Some("file"), None),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let issues = secret_issues(&mut config, Path::new("."), &MemoryStore::default());
+        let fields: Vec<_> = issues.iter().filter_map(|i| i.field.as_deref()).collect();
+        assert_eq!(fields, ["password", "smtp.password"]);
+        assert!(issues[0].message.contains("SHU_NET_KEEPER_TEST_UNSET"));
+    }
+
+    #[test]
+    fn test_resolve_encrypted() {
+        let dir = Path::new("test_secret_encrypted");
+        fs::create_dir_all(dir).unwrap();
+        let key = encryption::SecretKey::generate();
+        fs::write(dir.join(encryption::DEFAULT_KEY_FILE), key.to_base64()).unwrap();
+
+        let sources = SecretSources {
+            password_encrypted: Some(key.encrypt("decrypted")),
+            ..Default::default()
+        };
+        let resolved = resolve("password", None, &sources, "", dir, &MemoryStore::default());
+        fs::remove_dir_all(dir).unwrap();
+        assert_eq!(resolved.unwrap().as_deref(), Some("decrypted"));
+
+        let missing_key = resolve("password", None, &sources, "", dir, &MemoryStore::default());
+        assert!(matches!(missing_key, Err(ConfigError::Secret(_))));
+    }
+}
@@ -0,0 +1,174 @@
+//! TOML 配置文件中的位置信息
+//!
+//! 把字段路径（如 `smtp.sender`）对应到配置文件中的行列位置，用于在错误信息中指出出错的那一行。
+
+use crate::error::{ConfigIssue, SourceLocation};
+use std::ops::Range;
+use toml::Spanned;
+use toml::de::{DeTable, DeValue};
+
+/// 带位置信息的 TOML 文档
+pub(crate) struct TomlSource<'a> {
+    content: &'a str,
+    root: Option<DeTable<'a>>,
+}
+
+impl<'a> TomlSource<'a> {
+    /// 解析失败时仍可计算行列位置，只是无法按字段查找
+    pub(crate) fn new(content: &'a str) -> Self {
+        let root = DeTable::parse(content).ok().map(|table| table.into_inner());
+        Self { content, root }
+    }
+
+    /// 字段在文件中的位置：字段存在时指向它的值，缺失时指向所在的表
+    pub(crate) fn locate(&self, field: &str) -> Option<SourceLocation> {
+        let mut table = self.root.as_ref()?;
+        let mut parent: Option<&Spanned<DeValue>> = None;
+
+        for segment in field.split('.') {
+            // 数组元素写作 `profiles[0]`
+            let (key, index) = match segment.split_once('[') {
+                Some((key, rest)) => (key, rest.trim_end_matches(']').parse::<usize>().ok()),
+                None => (segment, None),
+            };
+            let mut value = table.get(key);
+            if let (Some(index), Some(DeValue::Array(items))) = (index, value.map(|v| v.get_ref()))
+            {
+                value = items.get(index);
+            }
+            let Some(value) = value else {
+                return parent.map(|value| self.location(value.span().start));
+            };
+            match value.get_ref() {
+                DeValue::Table(inner) => {
+                    table = inner;
+                    parent = Some(value);
+                }
+                _ => return Some(self.location(value.span().start)),
+            }
+        }
+        parent.map(|value| self.location(value.span().start))
+    }
+
+    /// 包含 `span` 的最内层字段路径
+    pub(crate) fn field_at(&self, span: &Range<usize>) -> Option<String> {
+        let mut path = Vec::new();
+        find_field(self.root.as_ref()?, span.start, &mut path).then(|| path.join("."))
+    }
+
+    /// 字节偏移对应的行列位置（均从 1 开始，列按字符计）
+    pub(crate) fn location(&self, offset: usize) -> SourceLocation {
+        let offset = offset.min(self.content.len());
+        let before = &self.content[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.content[offset..]
+            .find('\n')
+            .map_or(self.content.len(), |i| offset + i);
+
+        SourceLocation {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            snippet: self.content[line_start..line_end].trim_end().to_string(),
+        }
+    }
+
+    /// 为验证问题补充在文件中的位置
+    pub(crate) fn annotate(&self, issues: &mut [ConfigIssue]) {
+        for issue in issues {
+            if issue.location.is_none() {
+                issue.location = issue.field.as_deref().and_then(|field| self.locate(field));
+            }
+        }
+    }
+
+    /// 把 TOML 解析错误转换为问题列表，语法错误一次性全部报告
+    pub(crate) fn parse_issues(&self, err: &toml::de::Error) -> Vec<ConfigIssue> {
+        let (_, syntax_errors) = DeTable::parse_recoverable(self.content);
+        let errors = if syntax_errors.is_empty() {
+            vec![err.clone()]
+        } else {
+            syntax_errors
+        };
+
+        let mut issues: Vec<_> = errors
+            .iter()
+            .map(|e| ConfigIssue {
+                field: e.span().and_then(|span| self.field_at(&span)),
+                message: e.message().trim_end().to_string(),
+                location: e.span().map(|span| self.location(span.start)),
+            })
+            .collect();
+        issues.sort_by_key(|issue| issue.location.as_ref().map(|l| (l.line, l.column)));
+        issues
+    }
+}
+
+/// 表的 span 只覆盖表头，表内字段（包括 `[[profiles]]` 这样的表数组）需要逐层查找
+fn find_field(table: &DeTable, offset: usize, path: &mut Vec<String>) -> bool {
+    for (key, value) in table.iter() {
+        path.push(key.get_ref().to_string());
+        let found = match value.get_ref() {
+            DeValue::Table(inner) => find_field(inner, offset, path),
+            DeValue::Array(items) => items.iter().enumerate().any(|(i, item)| {
+                let DeValue::Table(inner) = item.get_ref() else {
+                    return false;
+                };
+                let last = path.len() - 1;
+                let key = path[last].clone();
+                path[last] = format!("{}[{}]", key, i);
+                find_field(inner, offset, path) || {
+                    path[last] = key;
+                    false
+                }
+            }),
+            _ => false,
+        };
+        if found || key.span().contains(&offset) || value.span().contains(&offset) {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = r#"username = "12345678"
+smtp_enabled = true
+
+[smtp]
+server = "smtp.qq.com"
+sender = "不是邮箱"
+"#;
+
+    #[test]
+    fn test_locate_fields() {
+        let source = TomlSource::new(CONTENT);
+
+        let sender = source.locate("smtp.sender").unwrap();
+        assert_eq!((sender.line, sender.column), (6, 10));
+        assert_eq!(sender.snippet, r#"sender = "不是邮箱""#);
+
+        // 缺失的字段指向所在的表
+        assert_eq!(source.locate("smtp.port").unwrap().line, 4);
+        assert!(source.locate("password").is_none());
+
+        let offset = CONTENT.find("smtp.qq.com").unwrap();
+        assert_eq!(
+            source.field_at(&(offset..offset + 1)).as_deref(),
+            Some("smtp.server")
+        );
+    }
+
+    #[test]
+    fn test_parse_issues() {
+        let content = "username = \"12345678\"\ninterval = \n";
+        let err = toml::from_str::<toml::Table>(content).unwrap_err();
+        let issues = TomlSource::new(content).parse_issues(&err);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].location.as_ref().unwrap().line, 2);
+    }
+}
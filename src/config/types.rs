@@ -1,44 +1,267 @@
-use crate::constants::DEFAULT_CHECK_INTERVAL;
+use crate::config::migration::CONFIG_VERSION;
+use crate::constants::{
+    DEFAULT_CHECK_INTERVAL, DEFAULT_HOOK_TIMEOUT, DEFAULT_HTTP_BIND, DEFAULT_USERNAME_LENGTH,
+};
+use ipnet::IpNet;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+/// shu-net-keeper 配置文件
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
+#[schemars(title = "shu-net-keeper", extend(
+    // smtp_enabled = true 时必须配置 [smtp]（SMTP 密码可来自其他来源，不在此要求）
+    "if" = { "properties": { "smtp_enabled": { "const": true } }, "required": ["smtp_enabled"] },
+    "then" = {
+        "required": ["smtp"],
+        "properties": {
+            "smtp": { "type": "object", "required": ["server", "port", "sender", "receiver"] }
+        }
+    }
+))]
 pub struct APPConfig {
-    /// 用户名（学号）
+    /// 配置格式版本，请勿修改
+    #[serde(default = "default_version")]
+    #[schemars(range(max = CONFIG_VERSION))]
+    pub version: u32,
+
+    /// 用户名（学号），格式由 account 规则决定
+    #[schemars(length(min = 1))]
     pub username: String,
 
-    /// 密码
+    /// 密码（也可以通过 password_encrypted、password_env 等字段提供，最多只能指定一个）
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub password: String,
 
+    /// 密码的其他来源
+    #[serde(flatten)]
+    pub password_from: SecretSources,
+
+    /// 账号格式规则，默认为 8 位数字学号
+    #[serde(default, skip_serializing_if = "AccountRule::is_default")]
+    pub account: AccountRule,
+
     /// 检查间隔（秒）
     #[serde(default = "default_interval")]
+    #[schemars(range(min = 1))]
     pub interval: u64,
 
+    /// 是否启用邮件通知
     #[serde(default)]
     pub smtp_enabled: bool,
 
+    /// SMTP 邮件通知配置，smtp_enabled 为 true 时必填
     #[serde(default)]
     pub smtp: Option<SmtpConfig>,
+
+    /// 事件钩子命令
+    #[serde(default)]
+    pub hooks: Option<HooksConfig>,
+
+    /// 内置 HTTP 服务（健康检查、状态查询），未配置时不启动
+    #[serde(default)]
+    pub http: Option<HttpConfig>,
+
+    /// 网络配置：按当前网络自动选择，都不匹配时守护进程空闲；未配置时总是运行
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<ProfileConfig>,
+}
+
+impl Default for APPConfig {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            username: String::new(),
+            password: String::new(),
+            password_from: SecretSources::default(),
+            account: AccountRule::default(),
+            interval: DEFAULT_CHECK_INTERVAL,
+            smtp_enabled: false,
+            smtp: None,
+            hooks: None,
+            http: None,
+            profiles: Vec::new(),
+        }
+    }
+}
+
+fn default_version() -> u32 {
+    CONFIG_VERSION
 }
 
 fn default_interval() -> u64 {
     DEFAULT_CHECK_INTERVAL
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+/// 账号格式规则（`[account]` 中的 `rule` 字段）
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, JsonSchema)]
+#[serde(tag = "rule", rename_all = "lowercase")]
+pub enum AccountRule {
+    /// 长度范围（按字符计），可要求全部为数字
+    Length {
+        /// 最小长度
+        #[schemars(range(min = 1))]
+        min_length: usize,
+        /// 最大长度，不小于 min_length
+        #[schemars(range(min = 1))]
+        max_length: usize,
+        /// 是否只能包含数字
+        #[serde(default)]
+        digits_only: bool,
+    },
+    /// 整个账号需要匹配的正则表达式
+    Regex {
+        /// 正则表达式，需匹配整个账号
+        #[schemars(extend("format" = "regex"))]
+        pattern: String,
+    },
+    /// 不检查格式，只要求非空
+    #[serde(rename = "none")]
+    Unrestricted,
+}
+
+impl Default for AccountRule {
+    fn default() -> Self {
+        AccountRule::Length {
+            min_length: DEFAULT_USERNAME_LENGTH,
+            max_length: DEFAULT_USERNAME_LENGTH,
+            digits_only: true,
+        }
+    }
+}
+
+impl AccountRule {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// SMTP 邮件通知配置
+#[derive(Debug, Default, Deserialize, Serialize, Clone, JsonSchema)]
 pub struct SmtpConfig {
+    /// SMTP 服务器地址
+    #[schemars(length(min = 1), example = &"smtp.qq.com")]
     pub server: Option<String>,
+    /// SMTP 端口，465 使用 SSL，其他端口使用 STARTTLS
+    #[schemars(range(min = 1), example = 465)]
     pub port: Option<u16>,
+    /// 发件人邮箱
+    #[schemars(email)]
     pub sender: Option<String>,
+    /// 邮箱密码或授权码（也可以通过 password_env 等字段提供，最多只能指定一个）
+    #[schemars(length(min = 1))]
     pub password: Option<String>,
+    /// 密码的其他来源
+    #[serde(flatten)]
+    pub password_from: SecretSources,
+    /// 收件人邮箱
+    #[schemars(email)]
     pub receiver: Option<String>,
 }
 
+/// 密码的外部来源，与明文 password 字段最多只能指定一个
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq, JsonSchema)]
+pub struct SecretSources {
+    /// 加密后的密码（`shu-net-keeper encrypt-password` 生成），密钥来自环境变量或密钥文件
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_encrypted: Option<String>,
+
+    /// 从指定的环境变量读取
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_env: Option<String>,
+
+    /// 从文件读取（如 Docker / Kubernetes secrets），相对路径相对于配置文件所在目录
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_file: Option<String>,
+
+    /// 执行命令，取标准输出的第一行（如 `pass show shu`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_command: Option<String>,
+
+    /// 从系统密钥环读取，值为条目名称（需要启用 keyring 功能编译）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password_keyring: Option<String>,
+}
+
+/// 事件钩子命令
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
+pub struct HooksConfig {
+    /// 钩子命令超时时间（秒）
+    #[serde(default = "default_hook_timeout")]
+    #[schemars(range(min = 1))]
+    pub timeout: u64,
+
+    /// 登录成功
+    pub on_login_success: Option<String>,
+
+    /// 登录失败
+    pub on_login_failure: Option<String>,
+
+    /// IP 地址变化
+    pub on_ip_changed: Option<String>,
+
+    /// 网络断开
+    pub on_offline: Option<String>,
+
+    /// 网络恢复
+    pub on_online: Option<String>,
+}
+
+fn default_hook_timeout() -> u64 {
+    DEFAULT_HOOK_TIMEOUT
+}
+
+/// 内置 HTTP 服务
+#[derive(Debug, Deserialize, Serialize, Clone, JsonSchema)]
+pub struct HttpConfig {
+    /// 监听地址（IP:端口）
+    #[serde(default = "default_http_bind")]
+    #[schemars(example = &"127.0.0.1:9870")]
+    pub bind: String,
+}
+
+fn default_http_bind() -> String {
+    DEFAULT_HTTP_BIND.to_string()
+}
+
+/// 网络配置（`[[profiles]]`），指定的条件全部满足时匹配，同一条件中的多个值满足其一即可
+#[derive(Debug, Default, Deserialize, Serialize, Clone, JsonSchema)]
+pub struct ProfileConfig {
+    /// 名称
+    #[schemars(length(min = 1))]
+    pub name: String,
+
+    /// 已连接的 Wi-Fi 名称
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ssid: Vec<String>,
+
+    /// 网卡名称（如 eth0、en0、以太网）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interface: Vec<String>,
+
+    /// 本机地址所在网段（CIDR，如 10.10.0.0/16）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subnet: Vec<String>,
+
+    /// 默认网关地址
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gateway: Vec<String>,
+
+    /// 该网络下的检查间隔（秒），未设置时使用全局 interval
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(range(min = 1))]
+    pub interval: Option<u64>,
+}
+
 // 验证后的配置（所有字段都不是 Option）
 pub struct APPConfigValidated {
     pub username: String,
     pub password: String,
     pub interval: u64,
     pub smtp: Option<SmtpConfigValidated>, // 如果 enabled = false，这里是 None
+    pub hooks: HooksConfigValidated,
+    pub http: Option<HttpConfigValidated>, // 未配置 [http] 时为 None
+    pub profiles: Vec<ProfileValidated>,   // 为空时不按网络选择，总是运行
 }
 
 pub struct SmtpConfigValidated {
@@ -48,3 +271,30 @@ pub struct SmtpConfigValidated {
     pub password: String,
     pub receiver: String,
 }
+
+// 未配置的事件为 None
+#[derive(Debug, Clone, Default)]
+pub struct HooksConfigValidated {
+    pub timeout: u64,
+    pub on_login_success: Option<String>,
+    pub on_login_failure: Option<String>,
+    pub on_ip_changed: Option<String>,
+    pub on_offline: Option<String>,
+    pub on_online: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpConfigValidated {
+    pub bind: SocketAddr,
+}
+
+// 条件为空表示不限制
+#[derive(Debug, Clone, Default)]
+pub struct ProfileValidated {
+    pub name: String,
+    pub ssid: Vec<String>,
+    pub interface: Vec<String>,
+    pub subnet: Vec<IpNet>,
+    pub gateway: Vec<IpAddr>,
+    pub interval: Option<u64>,
+}
@@ -1,60 +1,97 @@
 use crate::config::types::*;
-use crate::constants::REQUIRED_USERNAME_LENGTH;
-use crate::error::{ConfigError, ConfigResult, ValidationError};
+use crate::error::{ConfigError, ConfigIssue, ConfigResult, ValidationError};
+use regex::Regex;
 use tracing::{debug, error, info};
 use validator::validate_email;
 
-/// 统一的 Option 字段验证器
+/// 收集验证过程中发现的所有问题
+#[derive(Default)]
+struct Issues(Vec<ConfigIssue>);
+
+impl Issues {
+    fn add(&mut self, field: &str, message: impl ToString) {
+        self.0.push(ConfigIssue::new(field, message.to_string()));
+    }
+}
+
+/// 统一的 Option 字段验证器，发现问题时记录并返回 None
 struct FieldValidator;
 
 impl FieldValidator {
-    fn require_string(value: &Option<String>, field_name: &str) -> ConfigResult<String> {
-        let s = value
-            .as_ref()
-            .ok_or_else(|| ValidationError::MissingField(field_name.to_string()))?;
-
-        if s.is_empty() {
-            return Err(ValidationError::EmptyField(field_name.to_string()).into());
+    fn require_string(
+        issues: &mut Issues,
+        value: &Option<String>,
+        field: &str,
+        label: &str,
+    ) -> Option<String> {
+        match value.as_deref() {
+            None => issues.add(field, ValidationError::MissingField(label.to_string())),
+            Some("") => issues.add(field, ValidationError::EmptyField(label.to_string())),
+            Some(s) => return Some(s.to_string()),
         }
-
-        Ok(s.clone())
+        None
     }
 
-    fn require_email(value: &Option<String>, field_name: &str) -> ConfigResult<String> {
-        let email = Self::require_string(value, field_name)?;
+    fn require_email(
+        issues: &mut Issues,
+        value: &Option<String>,
+        field: &str,
+        label: &str,
+    ) -> Option<String> {
+        let email = Self::require_string(issues, value, field, label)?;
 
         if !validate_email(&email) {
-            return Err(ValidationError::InvalidEmail(email).into());
+            issues.add(field, ValidationError::InvalidEmail(email));
+            return None;
         }
 
-        Ok(email)
+        Some(email)
     }
 
-    fn require_port(value: Option<u16>, field_name: &str) -> ConfigResult<u16> {
-        let port = value.ok_or_else(|| ValidationError::MissingField(field_name.to_string()))?;
-
-        if port == 0 {
-            return Err(ValidationError::InvalidPort(port).into());
+    fn require_port(
+        issues: &mut Issues,
+        value: Option<u16>,
+        field: &str,
+        label: &str,
+    ) -> Option<u16> {
+        match value {
+            None => issues.add(field, ValidationError::MissingField(label.to_string())),
+            Some(0) => issues.add(field, ValidationError::InvalidPort(0)),
+            Some(port) => return Some(port),
         }
-
-        Ok(port)
+        None
     }
 }
 
+/// 验证配置，一次性收集所有问题（字段路径如 `smtp.sender`）
 pub fn validate_config(config: &APPConfig) -> ConfigResult<APPConfigValidated> {
     debug!("开始验证配置...");
+    let mut issues = Issues::default();
 
-    validate_username(&config.username)?;
-    validate_password(&config.password)?;
+    validate_username(&mut issues, &config.username, &config.account);
+    validate_password(&mut issues, &config.password);
+    if config.interval == 0 {
+        issues.add("interval", ValidationError::InvalidInterval(0));
+    }
 
     let validated_smtp = if config.smtp_enabled {
         info!("SMTP 已启用，验证 SMTP 配置...");
-        Some(validate_smtp_config(config.smtp.as_ref())?)
+        validate_smtp_config(&mut issues, config.smtp.as_ref())
     } else {
         info!("SMTP 未启用，跳过邮件通知");
         None
     };
 
+    let validated_hooks = validate_hooks_config(&mut issues, config.hooks.as_ref());
+    let validated_http = validate_http_config(&mut issues, config.http.as_ref());
+    let validated_profiles = validate_profiles(&mut issues, &config.profiles);
+
+    if !issues.0.is_empty() {
+        let err = ConfigError::Invalid { issues: issues.0 };
+        error!("{}", err);
+        return Err(err);
+    }
+
     info!("配置验证完成");
 
     Ok(APPConfigValidated {
@@ -62,51 +99,304 @@ pub fn validate_config(config: &APPConfig) -> ConfigResult<APPConfigValidated> {
         password: config.password.clone(),
         interval: config.interval,
         smtp: validated_smtp,
+        hooks: validated_hooks,
+        http: validated_http,
+        profiles: validated_profiles,
     })
 }
 
-fn validate_username(username: &str) -> ConfigResult<()> {
-    if username.len() != REQUIRED_USERNAME_LENGTH {
-        error!(
-            "用户名长度验证失败: 期望{}位，实际{}位",
-            REQUIRED_USERNAME_LENGTH,
-            username.len()
-        );
-        return Err(ValidationError::InvalidUsername(format!(
-            "用户名必须是{}位，当前为{}位",
-            REQUIRED_USERNAME_LENGTH,
-            username.len()
-        ))
-        .into());
+fn validate_username(issues: &mut Issues, username: &str, rule: &AccountRule) {
+    // 规则本身有误时不再检查用户名
+    if let Err(e) = check_account_rule(rule) {
+        let field = match rule {
+            AccountRule::Regex { .. } => "account.pattern",
+            _ => "account",
+        };
+        issues.add(field, ValidationError::InvalidAccountRule(e));
+        return;
     }
 
-    if !username.chars().all(|c| c.is_ascii_digit()) {
-        error!("用户名格式验证失败: 包含非数字字符");
-        return Err(ValidationError::InvalidUsername("用户名必须全部是数字".to_string()).into());
+    match check_username(username, rule) {
+        Ok(()) => debug!("用户名验证通过: {}", username),
+        Err(e) => issues.add("username", ValidationError::InvalidUsername(e)),
     }
+}
 
-    debug!("用户名验证通过: {}", username);
-    Ok(())
+/// 检查账号规则本身是否有效
+fn check_account_rule(rule: &AccountRule) -> Result<(), String> {
+    match rule {
+        AccountRule::Length {
+            min_length,
+            max_length,
+            ..
+        } if *min_length == 0 || min_length > max_length => Err(format!(
+            "长度范围 {}~{} 无效，最小长度须大于 0 且不大于最大长度",
+            min_length, max_length
+        )),
+        AccountRule::Regex { pattern } => compile_pattern(pattern).map(|_| ()),
+        _ => Ok(()),
+    }
+}
+
+/// 正则需要匹配整个账号
+fn compile_pattern(pattern: &str) -> Result<Regex, String> {
+    Regex::new(pattern)
+        .and_then(|_| Regex::new(&format!("^(?:{})$", pattern)))
+        .map_err(|e| {
+            // regex 的错误信息是多行的，只保留最后一行的原因
+            let message = e.to_string();
+            let reason = message.lines().last().unwrap_or_default();
+            format!(
+                "正则表达式 {} 无效: {}",
+                pattern,
+                reason.trim_start_matches("error: ")
+            )
+        })
+}
+
+/// 按账号规则检查用户名，返回不符合的原因（GUI 表单与配置验证共用）
+pub fn check_username(username: &str, rule: &AccountRule) -> Result<(), String> {
+    match rule {
+        AccountRule::Length {
+            min_length,
+            max_length,
+            digits_only,
+        } => {
+            let length = username.chars().count();
+            if !(*min_length..=*max_length).contains(&length) {
+                return Err(if min_length == max_length {
+                    format!("用户名必须是{}位，当前为{}位", min_length, length)
+                } else {
+                    format!(
+                        "用户名必须是{}~{}位，当前为{}位",
+                        min_length, max_length, length
+                    )
+                });
+            }
+            if *digits_only && !username.chars().all(|c| c.is_ascii_digit()) {
+                return Err("用户名必须全部是数字".to_string());
+            }
+            Ok(())
+        }
+        AccountRule::Regex { pattern } => {
+            if compile_pattern(pattern)?.is_match(username) {
+                Ok(())
+            } else {
+                Err(format!("用户名不匹配规则 {}", pattern))
+            }
+        }
+        AccountRule::Unrestricted if username.is_empty() => Err("用户名不能为空".to_string()),
+        AccountRule::Unrestricted => Ok(()),
+    }
 }
 
-fn validate_password(password: &str) -> ConfigResult<()> {
+fn validate_password(issues: &mut Issues, password: &str) {
     if password.is_empty() {
-        error!("密码验证失败: 密码为空");
-        return Err(ValidationError::EmptyField("密码".to_string()).into());
+        issues.add("password", ValidationError::EmptyField("密码".to_string()));
+    } else {
+        debug!("密码验证通过");
     }
-    debug!("密码验证通过");
-    Ok(())
 }
 
-fn validate_smtp_config(smtp: Option<&SmtpConfig>) -> ConfigResult<SmtpConfigValidated> {
-    let smtp =
-        smtp.ok_or_else(|| ConfigError::SmtpConfig("SMTP 已启用但未配置 [smtp] 部分".to_string()))?;
+fn validate_smtp_config(
+    issues: &mut Issues,
+    smtp: Option<&SmtpConfig>,
+) -> Option<SmtpConfigValidated> {
+    let Some(smtp) = smtp else {
+        issues.add(
+            "smtp",
+            ConfigError::SmtpConfig("SMTP 已启用但未配置 [smtp] 部分".to_string()),
+        );
+        return None;
+    };
+
+    // 先验证所有字段再组装，保证每个问题都被记录
+    let server = FieldValidator::require_string(issues, &smtp.server, "smtp.server", "SMTP 服务器");
+    let port = FieldValidator::require_port(issues, smtp.port, "smtp.port", "SMTP 端口");
+    let sender = FieldValidator::require_email(issues, &smtp.sender, "smtp.sender", "发件人邮箱");
+    let password =
+        FieldValidator::require_string(issues, &smtp.password, "smtp.password", "SMTP 密码");
+    let receiver =
+        FieldValidator::require_email(issues, &smtp.receiver, "smtp.receiver", "接收邮箱");
 
-    Ok(SmtpConfigValidated {
-        server: FieldValidator::require_string(&smtp.server, "SMTP 服务器")?,
-        port: FieldValidator::require_port(smtp.port, "SMTP 端口")?,
-        sender: FieldValidator::require_email(&smtp.sender, "发件人邮箱")?,
-        password: FieldValidator::require_string(&smtp.password, "SMTP 密码")?,
-        receiver: FieldValidator::require_email(&smtp.receiver, "接收邮箱")?,
+    Some(SmtpConfigValidated {
+        server: server?,
+        port: port?,
+        sender: sender?,
+        password: password?,
+        receiver: receiver?,
     })
 }
+
+fn validate_hooks_config(issues: &mut Issues, hooks: Option<&HooksConfig>) -> HooksConfigValidated {
+    let Some(hooks) = hooks else {
+        return HooksConfigValidated::default();
+    };
+
+    if hooks.timeout == 0 {
+        issues.add(
+            "hooks.timeout",
+            ConfigError::HooksConfig("钩子超时时间必须大于 0 秒".to_string()),
+        );
+    }
+
+    // 空字符串视为未配置
+    let command = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+
+    let validated = HooksConfigValidated {
+        timeout: hooks.timeout,
+        on_login_success: command(&hooks.on_login_success),
+        on_login_failure: command(&hooks.on_login_failure),
+        on_ip_changed: command(&hooks.on_ip_changed),
+        on_offline: command(&hooks.on_offline),
+        on_online: command(&hooks.on_online),
+    };
+
+    debug!("钩子配置验证完成");
+    validated
+}
+
+fn validate_http_config(
+    issues: &mut Issues,
+    http: Option<&HttpConfig>,
+) -> Option<HttpConfigValidated> {
+    let http = http?;
+
+    match http.bind.trim().parse() {
+        Ok(bind) => {
+            debug!("HTTP 服务配置验证通过");
+            Some(HttpConfigValidated { bind })
+        }
+        Err(_) => {
+            issues.add(
+                "http.bind",
+                ConfigError::HttpConfig(format!(
+                    "监听地址格式不正确: {}（示例: 127.0.0.1:9870）",
+                    http.bind
+                )),
+            );
+            None
+        }
+    }
+}
+
+fn validate_profiles(issues: &mut Issues, profiles: &[ProfileConfig]) -> Vec<ProfileValidated> {
+    let mut validated: Vec<ProfileValidated> = Vec::new();
+
+    for (i, profile) in profiles.iter().enumerate() {
+        let field = |name: &str| format!("profiles[{}].{}", i, name);
+        let name = profile.name.trim();
+
+        if name.is_empty() {
+            issues.add(
+                &field("name"),
+                ValidationError::EmptyField("网络配置名称".to_string()),
+            );
+        } else if validated.iter().any(|p| p.name == name) {
+            issues.add(
+                &field("name"),
+                ConfigError::ProfileConfig(format!("网络配置名称重复: {}", name)),
+            );
+        }
+
+        let subnet = profile
+            .subnet
+            .iter()
+            .filter_map(|s| match s.trim().parse() {
+                Ok(net) => Some(net),
+                Err(_) => {
+                    issues.add(
+                        &field("subnet"),
+                        ConfigError::ProfileConfig(format!(
+                            "网段格式不正确: {}（示例: 10.10.0.0/16）",
+                            s
+                        )),
+                    );
+                    None
+                }
+            })
+            .collect();
+        let gateway = profile
+            .gateway
+            .iter()
+            .filter_map(|s| match s.trim().parse() {
+                Ok(addr) => Some(addr),
+                Err(_) => {
+                    issues.add(
+                        &field("gateway"),
+                        ConfigError::ProfileConfig(format!("网关地址格式不正确: {}", s)),
+                    );
+                    None
+                }
+            })
+            .collect();
+        if profile.interval == Some(0) {
+            issues.add(
+                &field("interval"),
+                ConfigError::ProfileConfig("检查间隔必须大于 0 秒".to_string()),
+            );
+        }
+
+        validated.push(ProfileValidated {
+            name: name.to_string(),
+            ssid: profile.ssid.clone(),
+            interface: profile.interface.clone(),
+            subnet,
+            gateway,
+            interval: profile.interval,
+        });
+    }
+
+    if !validated.is_empty() {
+        debug!("网络配置验证完成，共 {} 个", validated.len());
+    }
+    validated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collects_all_issues() {
+        let config = APPConfig {
+            username: "123".to_string(),
+            interval: 0,
+            smtp_enabled: true,
+            smtp: Some(SmtpConfig {
+                server: Some("smtp.qq.com".to_string()),
+                port: Some(0),
+                sender: Some("不是邮箱".to_string()),
+                ..Default::default()
+            }),
+            http: Some(HttpConfig {
+                bind: "localhost".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let Err(ConfigError::Invalid { issues }) = validate_config(&config) else {
+            panic!("配置应验证失败");
+        };
+        let fields: Vec<_> = issues.iter().filter_map(|i| i.field.as_deref()).collect();
+        assert_eq!(
+            fields,
+            [
+                "username",
+                "password",
+                "interval",
+                "smtp.port",
+                "smtp.sender",
+                "smtp.password",
+                "smtp.receiver",
+                "http.bind"
+            ]
+        );
+    }
+}
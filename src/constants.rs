@@ -1,6 +1,7 @@
 // 网络端点常量
 pub const CAMPUS_GATEWAY: &str = "http://10.10.9.9";
 pub const LOGIN_URL: &str = "http://10.10.9.9/eportal/InterFace.do?method=login";
+pub const LOGOUT_URL: &str = "http://10.10.9.9/eportal/InterFace.do?method=logout";
 pub const LOGIN_INDEX: &str = "http://10.10.9.9/eportal/index.jsp";
 pub const ONLINE_INFO_URL: &str = "http://10.10.9.9/eportal/InterFace.do?method=getOnlineUserInfo";
 
@@ -9,4 +10,12 @@ pub const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWeb
 
 // 配置默认值
 pub const DEFAULT_CHECK_INTERVAL: u64 = 10;
-pub const REQUIRED_USERNAME_LENGTH: usize = 8;
+pub const DEFAULT_HOOK_TIMEOUT: u64 = 30;
+pub const DEFAULT_HTTP_BIND: &str = "127.0.0.1:9870";
+pub const DEFAULT_USERNAME_LENGTH: usize = 8;
+
+// 定时暂停的最长时间（分钟），即一年
+pub const MAX_PAUSE_MINUTES: u64 = 60 * 24 * 365;
+
+// 断网记录保留天数，stats 最多只能统计这么长时间
+pub const STATS_RETENTION_DAYS: u32 = 90;
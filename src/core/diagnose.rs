@@ -0,0 +1,427 @@
+//! 连通性诊断
+//!
+//! 把登录流程拆成独立的步骤逐一执行（在线信息接口、网关、重定向链、登录页 URL 提取、
+//! queryString / MAC 解析、密码加密），记录每一步的结果和耗时。
+//! 输出中的 IP、MAC 和查询参数均已脱敏，可以直接贴到 issue 中。
+
+use super::login::{extract_mac_from_query_string, extract_query_string, extract_url_from_script};
+use super::network::query_online_info;
+use crate::constants::{CAMPUS_GATEWAY, USER_AGENT};
+use crate::rsa::PasswordEncryptor;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// 最多跟随的重定向次数
+const MAX_REDIRECTS: usize = 10;
+
+/// 未获取到 MAC 时加密测试使用的占位值
+const PLACEHOLDER_MAC: &str = "000000000000";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Pass,
+    Fail,
+    /// 前置步骤失败或不适用，未执行
+    Skip,
+}
+
+/// 单个诊断步骤的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticStep {
+    pub name: &'static str,
+    pub status: StepStatus,
+    pub elapsed_ms: u64,
+    /// 已脱敏的详细信息
+    pub details: Vec<String>,
+}
+
+impl DiagnosticStep {
+    fn new(
+        name: &'static str,
+        status: StepStatus,
+        elapsed: Duration,
+        details: Vec<String>,
+    ) -> Self {
+        Self {
+            name,
+            status,
+            elapsed_ms: elapsed.as_millis() as u64,
+            details,
+        }
+    }
+
+    fn skip(name: &'static str, reason: &str) -> Self {
+        Self::new(
+            name,
+            StepStatus::Skip,
+            Duration::ZERO,
+            vec![reason.to_string()],
+        )
+    }
+}
+
+/// 依次执行全部诊断步骤
+pub fn run() -> Vec<DiagnosticStep> {
+    let mut steps = Vec::new();
+
+    let logged_in = check_online_info(&mut steps);
+    let mac = match follow_gateway(&mut steps) {
+        Some(html) => check_login_page(&mut steps, &html, logged_in),
+        None => {
+            let reason = "未获取到网关页面";
+            steps.push(DiagnosticStep::skip("提取登录页 URL", reason));
+            steps.push(DiagnosticStep::skip("解析 queryString 和 MAC", reason));
+            None
+        }
+    };
+
+    // 加密只依赖内置公钥，没有 MAC 时用占位值，仍然可以检查
+    steps.push(check_encryption(mac.as_deref().unwrap_or(PLACEHOLDER_MAC)));
+    steps
+}
+
+/// 从网关页面中提取登录页 URL，并解析 queryString 和 MAC
+fn check_login_page(
+    steps: &mut Vec<DiagnosticStep>,
+    html: &str,
+    logged_in: bool,
+) -> Option<String> {
+    let started = Instant::now();
+    let login_url = match extract_url_from_script(html) {
+        Ok(url) => {
+            steps.push(DiagnosticStep::new(
+                "提取登录页 URL",
+                StepStatus::Pass,
+                started.elapsed(),
+                vec![format!("登录页: {}", redact_url(&url))],
+            ));
+            url
+        }
+        Err(e) => {
+            // 已登录时网关不再返回登录页跳转脚本，属于正常情况
+            let (status, reason) = if logged_in {
+                (StepStatus::Skip, "当前已登录，网关未返回登录页跳转")
+            } else {
+                (StepStatus::Fail, "无法提取登录页 URL")
+            };
+            steps.push(DiagnosticStep::new(
+                "提取登录页 URL",
+                status,
+                started.elapsed(),
+                vec![
+                    e.to_string(),
+                    format!("页面长度: {} 字节", html.len()),
+                    format!("页面开头: {}", summarize_html(html)),
+                ],
+            ));
+            steps.push(DiagnosticStep::skip("解析 queryString 和 MAC", reason));
+            return None;
+        }
+    };
+
+    let started = Instant::now();
+    let parsed = extract_query_string(&login_url).and_then(|query_string| {
+        let mac = extract_mac_from_query_string(&query_string)?;
+        Ok((query_string, mac))
+    });
+    match parsed {
+        Ok((query_string, mac)) => {
+            steps.push(DiagnosticStep::new(
+                "解析 queryString 和 MAC",
+                StepStatus::Pass,
+                started.elapsed(),
+                vec![
+                    format!("queryString 长度: {}", query_string.len()),
+                    format!("参数: {}", query_keys(&query_string).join(", ")),
+                    format!("MAC: {}", redact_mac(&mac)),
+                ],
+            ));
+            Some(mac)
+        }
+        Err(e) => {
+            steps.push(DiagnosticStep::new(
+                "解析 queryString 和 MAC",
+                StepStatus::Fail,
+                started.elapsed(),
+                vec![e.to_string()],
+            ));
+            None
+        }
+    }
+}
+
+/// 在线信息接口，返回当前是否已登录
+fn check_online_info(steps: &mut Vec<DiagnosticStep>) -> bool {
+    let started = Instant::now();
+    match query_online_info() {
+        Ok(info) => {
+            let details = match &info.user_ip {
+                Some(ip) => vec![
+                    "状态: 已登录".to_string(),
+                    format!("IP: {}", redact_ip(ip)),
+                    format!("服务: {}", info.service.as_deref().unwrap_or("—")),
+                ],
+                None => vec!["状态: 未登录".to_string()],
+            };
+            steps.push(DiagnosticStep::new(
+                "在线信息接口",
+                StepStatus::Pass,
+                started.elapsed(),
+                details,
+            ));
+            info.user_ip.is_some()
+        }
+        Err(e) => {
+            steps.push(DiagnosticStep::new(
+                "在线信息接口",
+                StepStatus::Fail,
+                started.elapsed(),
+                vec![e.to_string()],
+            ));
+            false
+        }
+    }
+}
+
+/// 访问网关并逐跳跟随重定向，成功时返回最终页面内容
+fn follow_gateway(steps: &mut Vec<DiagnosticStep>) -> Option<String> {
+    let agent = ureq::builder().redirects(0).build();
+    let mut url = CAMPUS_GATEWAY.to_string();
+    let mut hops = Vec::new();
+    let started = Instant::now();
+
+    for hop in 0..=MAX_REDIRECTS {
+        let hop_started = Instant::now();
+        let result = agent.get(&url).set("User-Agent", USER_AGENT).call();
+        let elapsed = hop_started.elapsed();
+
+        if hop == 0 {
+            let (status, detail) = match &result {
+                Ok(response) => (
+                    StepStatus::Pass,
+                    format!("{} 可达，状态码 {}", CAMPUS_GATEWAY, response.status()),
+                ),
+                Err(e) => (StepStatus::Fail, e.to_string()),
+            };
+            steps.push(DiagnosticStep::new(
+                "访问校园网关",
+                status,
+                elapsed,
+                vec![detail],
+            ));
+        }
+
+        let response = match result {
+            Ok(response) => response,
+            Err(_) if hop == 0 => {
+                steps.push(DiagnosticStep::skip("重定向链", "网关不可达"));
+                return None;
+            }
+            Err(e) => {
+                hops.push(hop_failure(&url, &e));
+                steps.push(DiagnosticStep::new(
+                    "重定向链",
+                    StepStatus::Fail,
+                    started.elapsed(),
+                    hops,
+                ));
+                return None;
+            }
+        };
+
+        let status = response.status();
+        hops.push(format!(
+            "{} → {} ({} ms)",
+            redact_url(&url),
+            status,
+            elapsed.as_millis()
+        ));
+
+        if !(300..400).contains(&status) {
+            let html = response.into_string();
+            let (result, detail) = match &html {
+                Ok(_) => (StepStatus::Pass, format!("共 {} 次重定向", hop)),
+                Err(e) => (StepStatus::Fail, format!("读取页面失败: {}", e)),
+            };
+            hops.push(detail);
+            steps.push(DiagnosticStep::new(
+                "重定向链",
+                result,
+                started.elapsed(),
+                hops,
+            ));
+            return html.ok();
+        }
+
+        let Some(location) = response.header("Location") else {
+            hops.push("重定向响应缺少 Location 头".to_string());
+            steps.push(DiagnosticStep::new(
+                "重定向链",
+                StepStatus::Fail,
+                started.elapsed(),
+                hops,
+            ));
+            return None;
+        };
+        url = resolve_location(&url, location);
+    }
+
+    hops.push(format!("重定向超过 {} 次", MAX_REDIRECTS));
+    steps.push(DiagnosticStep::new(
+        "重定向链",
+        StepStatus::Fail,
+        started.elapsed(),
+        hops,
+    ));
+    None
+}
+
+/// 加载 RSA 公钥并加密一个测试密码（不使用真实密码）
+fn check_encryption(mac: &str) -> DiagnosticStep {
+    let started = Instant::now();
+    let result = PasswordEncryptor::new()
+        .and_then(|encryptor| encryptor.encrypt_password(&format!("diagnose>{}", mac)));
+    match result {
+        Ok(encrypted) => DiagnosticStep::new(
+            "密码加密",
+            StepStatus::Pass,
+            started.elapsed(),
+            vec![format!("密文长度: {}", encrypted.len())],
+        ),
+        Err(e) => DiagnosticStep::new(
+            "密码加密",
+            StepStatus::Fail,
+            started.elapsed(),
+            vec![e.to_string()],
+        ),
+    }
+}
+
+/// 把 Location 头解析为绝对 URL
+fn resolve_location(current: &str, location: &str) -> String {
+    if location.contains("://") {
+        return location.to_string();
+    }
+
+    let scheme_end = current.find("://").map(|i| i + 3).unwrap_or(0);
+    let host_end = current[scheme_end..]
+        .find('/')
+        .map(|i| scheme_end + i)
+        .unwrap_or(current.len());
+    if location.starts_with('/') {
+        return format!("{}{}", &current[..host_end], location);
+    }
+
+    let base = current.rfind('/').filter(|&i| i >= host_end);
+    match base {
+        Some(i) => format!("{}/{}", &current[..i], location),
+        None => format!("{}/{}", &current[..host_end], location),
+    }
+}
+
+/// 隐藏 URL 中查询参数的值，只保留参数名
+/// 重定向失败的一跳：ureq 错误的 Display 以完整的请求 URL 开头，只输出状态码或错误类型
+fn hop_failure(url: &str, err: &ureq::Error) -> String {
+    let reason = match err {
+        ureq::Error::Status(code, _) => format!("状态码 {}", code),
+        ureq::Error::Transport(transport) => transport.kind().to_string(),
+    };
+    format!("{} → {}", redact_url(url), reason)
+}
+
+fn redact_url(url: &str) -> String {
+    match url.split_once('?') {
+        Some((base, query)) => format!("{}?{}", base, query_keys(query).join("&")),
+        None => url.to_string(),
+    }
+}
+
+fn query_keys(query: &str) -> Vec<String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) => format!("{}=***", key),
+            None => pair.to_string(),
+        })
+        .collect()
+}
+
+/// 只保留 IP 的前两段
+fn redact_ip(ip: &str) -> String {
+    let parts: Vec<&str> = ip.split('.').collect();
+    if parts.len() == 4 {
+        format!("{}.{}.*.*", parts[0], parts[1])
+    } else {
+        "***".to_string()
+    }
+}
+
+/// 只保留 MAC 的厂商前缀（前三段）
+fn redact_mac(mac: &str) -> String {
+    let separator = if mac.contains('-') { '-' } else { ':' };
+    let parts: Vec<&str> = mac.split(separator).collect();
+    if parts.len() == 6 {
+        let mut redacted = parts[..3].to_vec();
+        redacted.extend(["**"; 3]);
+        redacted.join(&separator.to_string())
+    } else {
+        format!("***（{} 个字符）", mac.len())
+    }
+}
+
+/// 页面开头的一小段内容（去除换行），便于判断网关返回了什么
+fn summarize_html(html: &str) -> String {
+    let text: String = html.split_whitespace().collect::<Vec<_>>().join(" ");
+    let summary: String = text.chars().take(120).collect();
+    redact_url(&summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redaction() {
+        assert_eq!(redact_ip("10.10.123.45"), "10.10.*.*");
+        assert_eq!(redact_mac("aa:bb:cc:dd:ee:ff"), "aa:bb:cc:**:**:**");
+        assert_eq!(redact_mac("aabbccddeeff"), "***（12 个字符）");
+        assert_eq!(
+            redact_url("http://10.10.9.9/eportal/index.jsp?wlanuserip=10.1.2.3&mac=aabb"),
+            "http://10.10.9.9/eportal/index.jsp?wlanuserip=***&mac=***"
+        );
+    }
+
+    #[test]
+    fn test_hop_failure_is_redacted() {
+        let url = "http://127.0.0.1:1/eportal/index.jsp?wlanuserip=10.10.123.45&mac=aabbccddeeff";
+        let err = ureq::get(url).call().unwrap_err();
+
+        let line = hop_failure(url, &err);
+        assert!(line.starts_with("http://127.0.0.1:1/eportal/index.jsp?wlanuserip=***&mac=***"));
+        assert!(!line.contains("10.10.123.45"));
+        assert!(!line.contains("aabbccddeeff"));
+    }
+
+    #[test]
+    fn test_resolve_location() {
+        let current = "http://10.10.9.9/a/b.jsp";
+        assert_eq!(
+            resolve_location(current, "http://example.com/x"),
+            "http://example.com/x"
+        );
+        assert_eq!(
+            resolve_location(current, "/eportal/index.jsp"),
+            "http://10.10.9.9/eportal/index.jsp"
+        );
+        assert_eq!(
+            resolve_location(current, "c.jsp"),
+            "http://10.10.9.9/a/c.jsp"
+        );
+        assert_eq!(
+            resolve_location("http://10.10.9.9", "c.jsp"),
+            "http://10.10.9.9/c.jsp"
+        );
+    }
+}
@@ -123,6 +123,21 @@ pub fn send_login_notification(
     send_email_with_config(smtp, subject, &body)
 }
 
+/// 发送测试邮件，用于验证 SMTP 配置
+pub fn send_test_notification(smtp: &SmtpConfigValidated, username: &str) -> EmailResult<()> {
+    info!("准备发送测试邮件，用户: {}", username);
+
+    let hostname = gethostname().to_string_lossy().to_string();
+    let body = format!(
+        "这是一封来自 shu-net-keeper 的测试邮件，收到说明 SMTP 配置正确。\n\n            账号: {}\n            主机名: {}\n            发送时间: {}",
+        username,
+        hostname,
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+    );
+
+    send_email_with_config(smtp, "校园网登录通知 - 测试邮件", &body)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
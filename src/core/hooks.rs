@@ -0,0 +1,245 @@
+use crate::config::HooksConfigValidated;
+use crate::error::{HookError, HookResult};
+use gethostname::gethostname;
+use std::io::Read;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+/// 子进程退出后等待输出读取完成的最长时间
+/// 钩子脚本可能在后台启动其他进程（如 frpc），这些进程会继承输出管道导致读取无法结束
+const OUTPUT_GRACE_PERIOD: Duration = Duration::from_secs(1);
+
+/// 守护进程事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    LoginSuccess,
+    LoginFailure,
+    IpChanged,
+    Offline,
+    Online,
+}
+
+impl HookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookEvent::LoginSuccess => "login_success",
+            HookEvent::LoginFailure => "login_failure",
+            HookEvent::IpChanged => "ip_changed",
+            HookEvent::Offline => "offline",
+            HookEvent::Online => "online",
+        }
+    }
+
+    /// 获取事件对应的钩子命令
+    fn command(self, hooks: &HooksConfigValidated) -> Option<&str> {
+        match self {
+            HookEvent::LoginSuccess => hooks.on_login_success.as_deref(),
+            HookEvent::LoginFailure => hooks.on_login_failure.as_deref(),
+            HookEvent::IpChanged => hooks.on_ip_changed.as_deref(),
+            HookEvent::Offline => hooks.on_offline.as_deref(),
+            HookEvent::Online => hooks.on_online.as_deref(),
+        }
+    }
+}
+
+/// 事件详情，以环境变量形式传给钩子命令
+#[derive(Debug, Clone, Default)]
+pub struct HookContext {
+    pub username: String,
+    pub ip: Option<String>,
+    pub old_ip: Option<String>,
+    pub error: Option<String>,
+}
+
+impl HookContext {
+    fn env_vars(&self, event: HookEvent) -> Vec<(&'static str, String)> {
+        let mut vars = vec![
+            ("SHU_EVENT", event.as_str().to_string()),
+            ("SHU_USERNAME", self.username.clone()),
+            ("SHU_HOSTNAME", gethostname().to_string_lossy().to_string()),
+            (
+                "SHU_TIMESTAMP",
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            ),
+        ];
+        if let Some(ip) = &self.ip {
+            vars.push(("SHU_IP", ip.clone()));
+        }
+        if let Some(old_ip) = &self.old_ip {
+            vars.push(("SHU_OLD_IP", old_ip.clone()));
+        }
+        if let Some(error) = &self.error {
+            vars.push(("SHU_ERROR", error.clone()));
+        }
+        vars
+    }
+}
+
+/// 钩子命令执行结果
+#[derive(Debug)]
+pub struct HookOutput {
+    pub status: ExitStatus,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// 触发事件钩子
+/// 命令在后台线程中执行，不阻塞守护进程主循环；失败只记录日志
+/// 返回执行钩子的线程，进程退出前需要 join 以免钩子被中断
+pub fn dispatch(
+    hooks: &HooksConfigValidated,
+    event: HookEvent,
+    context: HookContext,
+) -> Option<JoinHandle<()>> {
+    let command = event.command(hooks)?;
+
+    let command = command.to_string();
+    let timeout = Duration::from_secs(hooks.timeout);
+
+    let spawned = thread::Builder::new()
+        .name(format!("hook-{}", event.as_str()))
+        .spawn(move || {
+            info!("执行 {} 钩子: {}", event.as_str(), command);
+            match run_hook(&command, event, &context, timeout) {
+                Ok(output) => log_output(event, &output),
+                Err(e) => error!("✗ {} 钩子执行失败: {}", event.as_str(), e),
+            }
+        });
+
+    spawned
+        .inspect_err(|e| error!("✗ 创建 {} 钩子线程失败: {}", event.as_str(), e))
+        .ok()
+}
+
+/// 同步执行钩子命令，超时后终止子进程
+pub fn run_hook(
+    command: &str,
+    event: HookEvent,
+    context: &HookContext,
+    timeout: Duration,
+) -> HookResult<HookOutput> {
+    let mut child = shell_command(command)
+        .envs(context.env_vars(event))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| HookError::SpawnFailed(e.to_string()))?;
+
+    let stdout = spawn_reader(child.stdout.take());
+    let stderr = spawn_reader(child.stderr.take());
+
+    let status = wait_with_timeout(&mut child, timeout)?;
+
+    Ok(HookOutput {
+        status,
+        stdout: collect_output(stdout),
+        stderr: collect_output(stderr),
+    })
+}
+
+#[cfg(unix)]
+pub(crate) fn shell_command(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+#[cfg(windows)]
+pub(crate) fn shell_command(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(command);
+    cmd
+}
+
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> HookResult<ExitStatus> {
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return Ok(status),
+            Ok(None) if Instant::now() >= deadline => {
+                warn!("钩子命令超时，终止进程 (PID {})", child.id());
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(HookError::Timeout(timeout));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(100)),
+            Err(e) => return Err(HookError::WaitFailed(e.to_string())),
+        }
+    }
+}
+
+fn spawn_reader<R: Read + Send + 'static>(pipe: Option<R>) -> mpsc::Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    if let Some(mut pipe) = pipe {
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = pipe.read_to_end(&mut buf);
+            let _ = tx.send(String::from_utf8_lossy(&buf).into_owned());
+        });
+    }
+    rx
+}
+
+fn collect_output(rx: mpsc::Receiver<String>) -> String {
+    rx.recv_timeout(OUTPUT_GRACE_PERIOD).unwrap_or_default()
+}
+
+fn log_output(event: HookEvent, output: &HookOutput) {
+    for line in output.stdout.lines().filter(|l| !l.trim().is_empty()) {
+        info!("[{} 钩子] {}", event.as_str(), line);
+    }
+    for line in output.stderr.lines().filter(|l| !l.trim().is_empty()) {
+        warn!("[{} 钩子] {}", event.as_str(), line);
+    }
+
+    if output.status.success() {
+        info!("✓ {} 钩子执行完成", event.as_str());
+    } else {
+        error!("✗ {} 钩子退出状态异常: {}", event.as_str(), output.status);
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_hook_passes_env() {
+        let context = HookContext {
+            username: "12345678".to_string(),
+            ip: Some("10.0.0.2".to_string()),
+            old_ip: Some("10.0.0.1".to_string()),
+            error: None,
+        };
+
+        let output = run_hook(
+            "echo $SHU_EVENT $SHU_OLD_IP $SHU_IP",
+            HookEvent::IpChanged,
+            &context,
+            Duration::from_secs(5),
+        )
+        .unwrap();
+
+        assert!(output.status.success());
+        assert_eq!(output.stdout.trim(), "ip_changed 10.0.0.1 10.0.0.2");
+    }
+
+    #[test]
+    fn test_run_hook_timeout() {
+        let result = run_hook(
+            "sleep 5",
+            HookEvent::Offline,
+            &HookContext::default(),
+            Duration::from_millis(200),
+        );
+
+        let err = result.unwrap_err();
+        assert!(matches!(err, HookError::Timeout(_)));
+        assert_eq!(err.to_string(), "命令执行超时（0.2 秒）");
+    }
+}
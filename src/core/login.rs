@@ -1,7 +1,9 @@
-use crate::constants::{CAMPUS_GATEWAY, LOGIN_INDEX, LOGIN_URL, USER_AGENT};
+use crate::constants::{CAMPUS_GATEWAY, LOGIN_INDEX, LOGIN_URL, LOGOUT_URL, USER_AGENT};
 use crate::error::{LoginError, LoginResult};
+use crate::metrics;
 use crate::rsa::PasswordEncryptor;
 use serde::Deserialize;
+use std::time::Instant;
 use tracing::{debug, error, info, warn};
 
 /// 登录响应结构体
@@ -14,6 +16,15 @@ struct LoginResponse {
 }
 
 pub fn network_login(username: &str, password: &str) -> LoginResult<()> {
+    let result = login(username, password);
+    match &result {
+        Ok(()) => metrics::global().record_login(true, "ok"),
+        Err(e) => metrics::global().record_login(false, e.reason()),
+    }
+    result
+}
+
+fn login(username: &str, password: &str) -> LoginResult<()> {
     info!("开始网络登录，用户: {}", username);
 
     // 创建一个共享的 agent，确保 cookie 在整个登录流程中保持一致
@@ -63,6 +74,7 @@ pub fn network_login(username: &str, password: &str) -> LoginResult<()> {
     ];
 
     debug!("发送登录请求到 {}...", LOGIN_URL);
+    let started = Instant::now();
     let response = agent
         .post(LOGIN_URL)
         .set("User-Agent", USER_AGENT)
@@ -70,11 +82,12 @@ pub fn network_login(username: &str, password: &str) -> LoginResult<()> {
         .set("Accept-Language", "zh-CN,zh;q=0.9,en;q=0.8")
         .set("Host", "10.10.9.9")
         .set("Referer", &referer)
-        .send_form(form_data)
-        .map_err(|e| {
-            error!("登录请求失败: {}", e);
-            LoginError::Request(e.to_string())
-        })?;
+        .send_form(form_data);
+    metrics::global().observe_portal_request("login", started.elapsed());
+    let response = response.map_err(|e| {
+        error!("登录请求失败: {}", e);
+        LoginError::Request(e.to_string())
+    })?;
 
     let status = response.status();
     debug!("收到响应，状态码: {}", status);
@@ -109,12 +122,58 @@ pub fn network_login(username: &str, password: &str) -> LoginResult<()> {
     }
 }
 
+/// 注销在线会话
+/// `user_index` 来自在线用户信息（getOnlineUserInfo）中的 userIndex 字段
+pub fn network_logout(user_index: &str) -> LoginResult<()> {
+    info!("开始注销校园网会话...");
+
+    let started = Instant::now();
+    let response = ureq::post(LOGOUT_URL)
+        .set("User-Agent", USER_AGENT)
+        .set("Accept", "*/*")
+        .send_form(&[("userIndex", user_index)]);
+    metrics::global().observe_portal_request("logout", started.elapsed());
+    let response = response.map_err(|e| {
+        error!("注销请求失败: {}", e);
+        LoginError::Request(e.to_string())
+    })?;
+
+    let status = response.status();
+    let body = response.into_string().map_err(|e| {
+        error!("读取响应内容失败: {}", e);
+        LoginError::ResponseParse(e.to_string())
+    })?;
+    debug!("注销响应内容: {}", body);
+
+    let logout_response: LoginResponse = serde_json::from_str(&body).map_err(|e| {
+        error!("解析注销响应失败: {}", e);
+        LoginError::ResponseParse(e.to_string())
+    })?;
+
+    if logout_response.result == "success" {
+        info!("✓ 注销成功");
+        Ok(())
+    } else {
+        let error_message = logout_response
+            .message
+            .unwrap_or_else(|| "未知错误".to_string());
+        error!("✗ 注销失败: {}", error_message);
+        Err(LoginError::Authentication {
+            status,
+            message: error_message,
+        })
+    }
+}
+
 fn get_login_query_string_with_agent(agent: &ureq::Agent) -> LoginResult<String> {
     debug!("开始获取登录查询字符串...");
 
     // 1. 访问校园网关，让客户端自动跟随重定向链
     debug!("访问校园网关 {}，跟随重定向...", CAMPUS_GATEWAY);
-    let response = agent.get(CAMPUS_GATEWAY).call().map_err(|e| {
+    let started = Instant::now();
+    let response = agent.get(CAMPUS_GATEWAY).call();
+    metrics::global().observe_portal_request("gateway", started.elapsed());
+    let response = response.map_err(|e| {
         error!("访问校园网关失败: {}", e);
         LoginError::QueryString(e.to_string())
     })?;
@@ -143,7 +202,7 @@ fn get_login_query_string_with_agent(agent: &ureq::Agent) -> LoginResult<String>
 }
 
 /// 从HTML脚本中提取重定向URL
-fn extract_url_from_script(html: &str) -> LoginResult<String> {
+pub(crate) fn extract_url_from_script(html: &str) -> LoginResult<String> {
     use regex::Regex;
 
     let re = Regex::new(r"location\.href='([^']+)'").map_err(|e| {
@@ -169,7 +228,7 @@ fn extract_url_from_script(html: &str) -> LoginResult<String> {
 }
 
 /// 从 URL 中提取 query string（? 后面的部分）
-fn extract_query_string(url: &str) -> LoginResult<String> {
+pub(crate) fn extract_query_string(url: &str) -> LoginResult<String> {
     url.split('?')
         .nth(1) // 获取 ? 后面的部分
         .map(|s| s.to_string())
@@ -177,7 +236,7 @@ fn extract_query_string(url: &str) -> LoginResult<String> {
 }
 
 /// 从 queryString 中提取 mac 字段
-fn extract_mac_from_query_string(query_string: &str) -> LoginResult<String> {
+pub(crate) fn extract_mac_from_query_string(query_string: &str) -> LoginResult<String> {
     for pair in query_string.split('&') {
         if let Some((key, value)) = pair.split_once('=')
             && key == "mac"
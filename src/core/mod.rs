@@ -1,3 +1,6 @@
+pub mod diagnose;
 pub mod email;
+pub mod hooks;
 pub mod login;
+pub mod netenv;
 pub mod network;
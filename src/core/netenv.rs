@@ -0,0 +1,295 @@
+//! 当前网络环境检测
+//!
+//! 收集网卡、本机地址、默认网关和已连接的 Wi-Fi 名称，用于选择网络配置（`[[profiles]]`）。
+//! 默认网关和 Wi-Fi 名称通过系统命令获取，检测失败时视为没有该信息，不影响其他条件。
+
+use network_interface::{NetworkInterface, NetworkInterfaceConfig};
+use serde::Serialize;
+use std::net::IpAddr;
+use std::process::Command;
+use tracing::debug;
+
+/// 当前网络环境
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct NetworkEnvironment {
+    /// 有非回环地址的网卡
+    pub interfaces: Vec<String>,
+    /// 这些网卡上的地址
+    pub addresses: Vec<IpAddr>,
+    /// 默认网关
+    pub gateways: Vec<IpAddr>,
+    /// 已连接的 Wi-Fi 名称
+    pub ssids: Vec<String>,
+}
+
+/// 需要检测的信息（网关和 Wi-Fi 名称需要执行系统命令，只在用到时检测）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Detect {
+    pub gateways: bool,
+    pub ssids: bool,
+}
+
+/// 检测当前网络环境
+pub fn detect(what: Detect) -> NetworkEnvironment {
+    let mut env = NetworkEnvironment::default();
+
+    match NetworkInterface::show() {
+        Ok(interfaces) => {
+            for interface in interfaces.iter().filter(|i| !i.internal) {
+                let addresses: Vec<IpAddr> = interface
+                    .addr
+                    .iter()
+                    .map(|addr| addr.ip())
+                    .filter(|ip| !ip.is_loopback())
+                    .collect();
+                if addresses.is_empty() {
+                    continue;
+                }
+                if !env.interfaces.contains(&interface.name) {
+                    env.interfaces.push(interface.name.clone());
+                }
+                env.addresses.extend(addresses);
+            }
+        }
+        Err(e) => debug!("获取网卡列表失败: {}", e),
+    }
+
+    if what.gateways {
+        env.gateways = default_gateways();
+    }
+    if what.ssids {
+        env.ssids = connected_ssids();
+    }
+
+    debug!("当前网络环境: {:?}", env);
+    env
+}
+
+/// 执行系统命令并返回标准输出，失败时返回 None
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW：GUI 中执行时不弹出控制台窗口
+        cmd.creation_flags(0x0800_0000);
+    }
+
+    match cmd.output() {
+        Ok(output) if output.status.success() => {
+            Some(String::from_utf8_lossy(&output.stdout).into_owned())
+        }
+        Ok(output) => {
+            debug!("{} 执行失败: {}", program, output.status);
+            None
+        }
+        Err(e) => {
+            debug!("无法执行 {}: {}", program, e);
+            None
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn default_gateways() -> Vec<IpAddr> {
+    std::fs::read_to_string("/proc/net/route")
+        .map(|content| parse_proc_route(&content))
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "macos")]
+fn default_gateways() -> Vec<IpAddr> {
+    command_output("route", &["-n", "get", "default"])
+        .map(|output| parse_key_values(&output, "gateway"))
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|value| value.parse().ok())
+        .collect()
+}
+
+#[cfg(windows)]
+fn default_gateways() -> Vec<IpAddr> {
+    command_output("route", &["print", "-4", "0.0.0.0"])
+        .map(|output| parse_route_print(&output))
+        .unwrap_or_default()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn default_gateways() -> Vec<IpAddr> {
+    Vec::new()
+}
+
+#[cfg(target_os = "linux")]
+fn connected_ssids() -> Vec<String> {
+    // NetworkManager 优先，其次是 wireless-tools
+    // --rescan no：只读取缓存的列表，每次检查都触发主动扫描会造成延迟抖动和丢包
+    let nmcli = [
+        "-t",
+        "-f",
+        "active,ssid",
+        "dev",
+        "wifi",
+        "list",
+        "--rescan",
+        "no",
+    ];
+    if let Some(output) = command_output("nmcli", &nmcli) {
+        return parse_nmcli(&output);
+    }
+    command_output("iwgetid", &["-r"])
+        .map(|output| {
+            output
+                .lines()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "macos")]
+fn connected_ssids() -> Vec<String> {
+    // Wi-Fi 不一定是 en0，按硬件端口列表找出所有 Wi-Fi 网卡
+    let ports = command_output("networksetup", &["-listallhardwareports"]).unwrap_or_default();
+    wifi_devices(&ports)
+        .iter()
+        .filter_map(|device| command_output("ipconfig", &["getsummary", device.as_str()]))
+        .flat_map(|output| parse_key_values(&output, "SSID"))
+        .collect()
+}
+
+#[cfg(windows)]
+fn connected_ssids() -> Vec<String> {
+    command_output("netsh", &["wlan", "show", "interfaces"])
+        .map(|output| parse_key_values(&output, "SSID"))
+        .unwrap_or_default()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn connected_ssids() -> Vec<String> {
+    Vec::new()
+}
+
+/// 解析 /proc/net/route 中默认路由的网关（十六进制，主机字节序）
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_route(content: &str) -> Vec<IpAddr> {
+    const RTF_GATEWAY: u32 = 0x2;
+
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (destination, gateway, flags) = (fields.get(1)?, fields.get(2)?, fields.get(3)?);
+            let flags = u32::from_str_radix(flags, 16).ok()?;
+            if *destination != "00000000" || flags & RTF_GATEWAY == 0 {
+                return None;
+            }
+            let gateway = u32::from_str_radix(gateway, 16).ok()?;
+            Some(IpAddr::from(gateway.to_ne_bytes()))
+        })
+        .collect()
+}
+
+/// 解析 `nmcli -t -f active,ssid dev wifi` 中已连接的网络（`:` 转义为 `\:`）
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_nmcli(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.strip_prefix("yes:"))
+        .map(|ssid| ssid.replace("\\:", ":"))
+        .filter(|ssid| !ssid.is_empty())
+        .collect()
+}
+
+/// 解析 `key : value` 形式的输出中指定键的值（键完全匹配，如 SSID 不会匹配 BSSID）
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+fn parse_key_values(output: &str, key: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(k, _)| k.trim() == key)
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+/// 解析 `networksetup -listallhardwareports` 中 Wi-Fi 端口对应的网卡名称
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn wifi_devices(output: &str) -> Vec<String> {
+    let mut devices = Vec::new();
+    let mut is_wifi = false;
+    for line in output.lines() {
+        if let Some(port) = line.strip_prefix("Hardware Port:") {
+            is_wifi = matches!(port.trim(), "Wi-Fi" | "AirPort");
+        } else if let Some(device) = line.strip_prefix("Device:")
+            && is_wifi
+        {
+            devices.push(device.trim().to_string());
+        }
+    }
+    devices
+}
+
+/// 解析 Windows `route print -4 0.0.0.0` 中默认路由的网关
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_route_print(output: &str) -> Vec<IpAddr> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["0.0.0.0", "0.0.0.0", gateway, ..] => gateway.parse().ok(),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gateways() {
+        // 内核按主机字节序输出地址
+        let gateway = u32::from_ne_bytes([10, 10, 9, 1]);
+        let route = format!(
+            "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\n\
+             eth0\t00000000\t{:08X}\t0003\t0\t0\t100\t00000000\n\
+             eth0\t00000A0A\t00000000\t0001\t0\t0\t100\t0000FFFF\n",
+            gateway
+        );
+        assert_eq!(
+            parse_proc_route(&route),
+            vec!["10.10.9.1".parse::<IpAddr>().unwrap()]
+        );
+
+        let print = "IPv4 路由表\n  0.0.0.0          0.0.0.0      10.10.0.1    10.10.1.2     25\n";
+        assert_eq!(
+            parse_route_print(print),
+            vec!["10.10.0.1".parse::<IpAddr>().unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_parse_ssids() {
+        assert_eq!(
+            parse_nmcli("no:Other\nyes:Shu\\:ForAll\n"),
+            vec!["Shu:ForAll"]
+        );
+        assert_eq!(
+            parse_key_values(
+                "    BSSID : aa:bb\n    SSID                   : SHU-WiFi\n",
+                "SSID"
+            ),
+            vec!["SHU-WiFi"]
+        );
+
+        let ports = "Hardware Port: Ethernet\nDevice: en0\nEthernet Address: aa:bb\n\n\
+                     Hardware Port: Wi-Fi\nDevice: en1\nEthernet Address: cc:dd\n";
+        assert_eq!(wifi_devices(ports), vec!["en1"]);
+    }
+}
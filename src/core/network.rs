@@ -1,24 +1,95 @@
 use crate::constants::ONLINE_INFO_URL;
 use crate::error::{NetworkError, NetworkResult};
-use serde::Deserialize;
+use crate::metrics;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
 use tracing::{debug, error, info};
 
-/// 在线用户信息
-#[derive(Debug, Deserialize)]
-pub(crate) struct OnlineUserInfo {
-    #[serde(rename = "userIp")]
-    user_ip: Option<String>,
+/// 在线用户信息（getOnlineUserInfo 接口返回值）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OnlineUserInfo {
+    /// 在线会话索引，注销时使用
+    pub user_index: Option<String>,
+    pub user_id: Option<String>,
+    pub user_name: Option<String>,
+    /// 用户 IP（为 null 表示未登录）
+    pub user_ip: Option<String>,
+    pub user_mac: Option<String>,
+    pub service: Option<String>,
+    pub user_group: Option<String>,
+    /// 账户余额（部分门户返回数字，统一转为字符串）
+    #[serde(deserialize_with = "lenient_string")]
+    pub account_fee: Option<String>,
+    /// 门户页面展示的余额、流量等信息（JSON 数组编码成的字符串）
+    pub ball_info: Option<String>,
+}
+
+/// 门户展示的一项账户信息（如余额、已用流量）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PortalItem {
+    pub name: String,
+    pub value: String,
+}
+
+impl OnlineUserInfo {
+    /// 解析 ballInfo 中的账户信息，格式不符的条目会被忽略
+    pub fn portal_items(&self) -> Vec<PortalItem> {
+        let Some(ball_info) = self.ball_info.as_deref().filter(|s| !s.is_empty()) else {
+            return Vec::new();
+        };
+        let items: Vec<serde_json::Value> = match serde_json::from_str(ball_info) {
+            Ok(items) => items,
+            Err(e) => {
+                debug!("解析 ballInfo 失败: {}", e);
+                return Vec::new();
+            }
+        };
+
+        items
+            .iter()
+            .filter_map(|item| {
+                let name = item["displayName"].as_str().or(item["id"].as_str())?;
+                let value = match &item["value"] {
+                    serde_json::Value::String(value) => value.clone(),
+                    serde_json::Value::Number(value) => value.to_string(),
+                    _ => return None,
+                };
+                Some(PortalItem {
+                    name: name.to_string(),
+                    value,
+                })
+            })
+            .collect()
+    }
+}
+
+/// 接受字符串、数字或 null 的字段
+fn lenient_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(
+        match Option::<serde_json::Value>::deserialize(deserializer)? {
+            Some(serde_json::Value::String(value)) => Some(value),
+            Some(serde_json::Value::Number(value)) => Some(value.to_string()),
+            _ => None,
+        },
+    )
 }
 
 /// 查询在线用户信息（网络请求 + 解析）
 /// 返回值：
-/// - Ok(Some(info)): 成功获取到响应（无论是否已登录）
+/// - Ok(info): 成功获取到响应（无论是否已登录）
 /// - Err: 网络错误
-fn query_online_info() -> NetworkResult<OnlineUserInfo> {
+pub fn query_online_info() -> NetworkResult<OnlineUserInfo> {
     debug!("请求在线用户信息: {}", ONLINE_INFO_URL);
 
     let agent = ureq::agent();
-    let response = agent.get(ONLINE_INFO_URL).call().map_err(|e| {
+    let started = Instant::now();
+    let response = agent.get(ONLINE_INFO_URL).call();
+    metrics::global().observe_portal_request("online_info", started.elapsed());
+    let response = response.map_err(|e| {
         error!("请求在线用户信息失败: {}", e);
         if is_connection_error(&e) {
             NetworkError::NotConnected("未连接到校园网".to_string())
@@ -90,22 +161,28 @@ fn is_connection_error(err: &ureq::Error) -> bool {
 
 /// 检查网络连接状态
 /// 返回值：
-/// - Ok(true): 已连接且已登录
-/// - Ok(false): 未登录（网络可达但用户未登录）
+/// - Ok(Some(info)): 已连接且已登录，返回在线用户信息
+/// - Ok(None): 未登录（网络可达但用户未登录）
 /// - Err: 网络错误（无法连接到校园网）
 ///
 /// 若已登录且 `ip_status` 为 None，则将当前 IP 写入 `ip_status`，
 /// 用于在程序首次启动时就记录基准 IP，以便后续正确检测 IP 变化。
-pub fn check_network_connection(ip_status: &mut Option<String>) -> Result<bool, NetworkError> {
-    match get_host_ip() {
-        Ok(Some(ip)) => {
+pub fn check_network_connection(
+    ip_status: &mut Option<String>,
+) -> NetworkResult<Option<OnlineUserInfo>> {
+    let info = query_online_info()?;
+    match &info.user_ip {
+        Some(ip) => {
+            info!("成功获取主机 IP: {}", ip);
             if ip_status.is_none() {
-                *ip_status = Some(ip);
+                *ip_status = Some(ip.clone());
             }
-            Ok(true)
+            Ok(Some(info))
+        }
+        None => {
+            debug!("用户未登录校园网");
+            Ok(None)
         }
-        Ok(None) => Ok(false), // 未登录
-        Err(e) => Err(e),      // 网络错误
     }
 }
 
@@ -113,6 +190,31 @@ pub fn check_network_connection(ip_status: &mut Option<String>) -> Result<bool,
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_portal_items() {
+        let body = r#"{
+            "userIp": "10.0.0.1",
+            "accountFee": 12.5,
+            "ballInfo": "[{\"displayName\":\"余额\",\"id\":\"balance\",\"value\":\"12.50\"},{\"id\":\"flow\",\"value\":1024},{\"id\":\"broken\"}]"
+        }"#;
+        let info: OnlineUserInfo = serde_json::from_str(body).unwrap();
+
+        assert_eq!(info.account_fee.as_deref(), Some("12.5"));
+        assert_eq!(
+            info.portal_items(),
+            vec![
+                PortalItem {
+                    name: "余额".to_string(),
+                    value: "12.50".to_string()
+                },
+                PortalItem {
+                    name: "flow".to_string(),
+                    value: "1024".to_string()
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_get_host_ip() {
         let result = get_host_ip();
@@ -0,0 +1,353 @@
+//! 本地控制接口
+//!
+//! 守护进程在 Unix 域套接字上监听，协议为 JSON Lines：
+//! 客户端每行发送一个请求（如 `{"command":"status"}`），
+//! 守护进程每行返回一个响应（`{"ok":true,"data":...}` 或 `{"ok":false,"error":"..."}`）。
+
+use super::{CheckOutcome, Daemon};
+use crate::config;
+use crate::error::{ControlError, ControlResult, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Sender};
+use tracing::{error, info, warn};
+
+#[cfg(unix)]
+use std::io::{self, BufRead, BufReader, Write};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use std::path::Path;
+#[cfg(unix)]
+use std::time::Duration;
+#[cfg(unix)]
+use tracing::debug;
+
+const SOCKET_FILE_NAME: &str = "shu-net-keeper.sock";
+
+/// 客户端等待响应的超时时间（登录可能需要较长时间）
+#[cfg(unix)]
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 控制请求
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlRequest {
+    /// 查询守护进程状态
+    Status,
+    /// 查询最近一次获取的在线用户信息
+    Info,
+    /// 立即检查一次
+    Check,
+    /// 立即登录
+    Login,
+    /// 注销当前在线会话
+    Logout,
+    /// 暂停自动登录，`minutes` 为空时暂停直到手动恢复
+    Pause {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        minutes: Option<u64>,
+    },
+    /// 恢复自动登录
+    Resume,
+    /// 重新加载配置文件
+    Reload,
+}
+
+/// 控制响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControlResponse {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ControlResponse {
+    fn success(data: impl Serialize) -> Self {
+        Self {
+            ok: true,
+            data: serde_json::to_value(data).ok(),
+            error: None,
+        }
+    }
+
+    fn failure(message: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            data: None,
+            error: Some(message.into()),
+        }
+    }
+}
+
+/// 转发给守护循环处理的控制请求
+pub(crate) struct ControlCall {
+    request: ControlRequest,
+    reply: Sender<ControlResponse>,
+}
+
+impl ControlCall {
+    /// 守护进程内部发起、不需要回复的请求（如配置文件变化后重新加载）
+    pub(crate) fn internal(request: ControlRequest) -> Self {
+        let (reply, _) = mpsc::channel();
+        Self { request, reply }
+    }
+
+    /// 处理后是否需要重新计时（手动检查、登录）
+    pub(crate) fn resets_timer(&self) -> bool {
+        matches!(self.request, ControlRequest::Check | ControlRequest::Login)
+    }
+}
+
+/// 获取控制套接字路径
+pub fn get_socket_path() -> PathBuf {
+    super::get_runtime_dir().join(SOCKET_FILE_NAME)
+}
+
+/// 在默认路径启动控制接口，失败时仅记录警告，守护进程照常运行
+pub(crate) fn serve_default(calls: Sender<ControlCall>) {
+    #[cfg(unix)]
+    {
+        let path = get_socket_path();
+        match serve(&path, calls) {
+            Ok(()) => info!("控制接口已启动: {}", path.display()),
+            Err(e) => warn!("启动控制接口失败 {}: {}", path.display(), e),
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        drop(calls);
+        info!("当前平台不支持控制接口");
+    }
+}
+
+/// 在 `path` 上监听控制连接，收到的请求通过 `calls` 转发给守护循环
+#[cfg(unix)]
+pub(crate) fn serve(path: &Path, calls: Sender<ControlCall>) -> io::Result<()> {
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::thread;
+
+    // 调用方已持有实例锁，残留的套接字文件只可能来自已退出的旧进程
+    match fs::remove_file(path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    // 套接字位于 0700 的运行时目录中，bind 与设置权限之间其他用户也无法连接
+    let listener = UnixListener::bind(path)?;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let calls = calls.clone();
+                    thread::spawn(move || serve_connection(stream, calls));
+                }
+                Err(e) => warn!("接受控制连接失败: {}", e),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn serve_connection(stream: UnixStream, calls: Sender<ControlCall>) {
+    let reader = match stream.try_clone() {
+        Ok(reader) => BufReader::new(reader),
+        Err(e) => {
+            warn!("控制连接初始化失败: {}", e);
+            return;
+        }
+    };
+    let mut writer = stream;
+
+    for line in reader.lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<ControlRequest>(&line) {
+            Ok(request) => {
+                debug!("收到控制请求: {:?}", request);
+                let (reply, response) = mpsc::channel();
+                if calls.send(ControlCall { request, reply }).is_err() {
+                    break;
+                }
+                response
+                    .recv()
+                    .unwrap_or_else(|_| ControlResponse::failure("守护进程未响应"))
+            }
+            Err(e) => ControlResponse::failure(format!("无效的请求: {}", e)),
+        };
+
+        if write_line(&mut writer, &response).is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(unix)]
+fn write_line(writer: &mut impl Write, value: &impl Serialize) -> io::Result<()> {
+    let mut line = serde_json::to_string(value).map_err(io::Error::other)?;
+    line.push('\n');
+    writer.write_all(line.as_bytes())?;
+    writer.flush()
+}
+
+/// 在守护循环中处理一个控制请求并回复
+pub(crate) fn handle(daemon: &mut Daemon, call: ControlCall) {
+    info!("处理控制请求: {:?}", call.request);
+
+    let response = match call.request {
+        ControlRequest::Status => ControlResponse::success(daemon.status()),
+        ControlRequest::Info => match daemon.online_info() {
+            Some(online_info) => ControlResponse::success(online_info),
+            None => ControlResponse::failure("当前未登录，没有在线信息"),
+        },
+        ControlRequest::Check => outcome_response(daemon.check()),
+        ControlRequest::Login => outcome_response(daemon.login()),
+        ControlRequest::Logout => match super::logout() {
+            Ok(()) => ControlResponse::success(json!({ "paused": daemon.is_paused() })),
+            Err(e) => ControlResponse::failure(e.to_string()),
+        },
+        ControlRequest::Pause { minutes } => match daemon.pause(minutes) {
+            Ok(()) => ControlResponse::success(daemon.status()),
+            Err(e) => ControlResponse::failure(e.to_string()),
+        },
+        ControlRequest::Resume => {
+            daemon.resume();
+            ControlResponse::success(daemon.status())
+        }
+        ControlRequest::Reload => match config::load_config() {
+            Ok(config) => {
+                let bind = |http: Option<&config::HttpConfigValidated>| http.map(|h| h.bind);
+                if bind(config.http.as_ref()) != bind(daemon.config().http.as_ref()) {
+                    warn!("HTTP 服务配置的变化需要重启守护进程后生效");
+                }
+                daemon.set_config(config);
+                info!("✓ 配置已重新加载");
+                ControlResponse::success(daemon.status())
+            }
+            Err(e) => {
+                error!("重新加载配置失败，继续使用原配置: {}", e);
+                ControlResponse::failure(e.to_string())
+            }
+        },
+    };
+
+    let _ = call.reply.send(response);
+}
+
+fn outcome_response(result: Result<CheckOutcome>) -> ControlResponse {
+    match result {
+        Ok(outcome) => ControlResponse::success(outcome_to_json(&outcome)),
+        Err(e) => ControlResponse::failure(e.to_string()),
+    }
+}
+
+/// 把检查结果转换为控制接口和命令行 JSON 输出使用的格式
+pub fn outcome_to_json(outcome: &CheckOutcome) -> Value {
+    match outcome {
+        CheckOutcome::Online => json!({ "outcome": "online" }),
+        CheckOutcome::LoggedIn {
+            ip,
+            ip_changed,
+            notification,
+        } => json!({
+            "outcome": "logged_in",
+            "ip": ip,
+            "ip_changed": ip_changed,
+            "notification_error": notification
+                .as_ref()
+                .and_then(|r| r.as_ref().err())
+                .map(|e| e.to_string()),
+        }),
+        CheckOutcome::Paused => json!({ "outcome": "paused" }),
+        CheckOutcome::Idle => json!({ "outcome": "idle" }),
+    }
+}
+
+/// 向守护进程发送一个控制请求并等待响应
+#[cfg(unix)]
+pub fn send(path: &Path, request: &ControlRequest) -> ControlResult<ControlResponse> {
+    let stream = UnixStream::connect(path).map_err(|e| ControlError::Connect {
+        path: path.display().to_string(),
+        message: e.to_string(),
+    })?;
+    stream
+        .set_read_timeout(Some(CLIENT_TIMEOUT))
+        .map_err(|e| ControlError::Io(e.to_string()))?;
+
+    write_line(&mut &stream, request).map_err(|e| ControlError::Io(e.to_string()))?;
+
+    let mut line = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut line)
+        .map_err(|e| ControlError::Io(e.to_string()))?;
+    if line.is_empty() {
+        return Err(ControlError::Protocol("守护进程关闭了连接".to_string()));
+    }
+
+    serde_json::from_str(&line).map_err(|e| ControlError::Protocol(e.to_string()))
+}
+
+/// 向守护进程发送一个控制请求并等待响应
+#[cfg(not(unix))]
+pub fn send(_path: &std::path::Path, _request: &ControlRequest) -> ControlResult<ControlResponse> {
+    Err(ControlError::Unsupported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_format() {
+        let request: ControlRequest = serde_json::from_str(r#"{"command":"status"}"#).unwrap();
+        assert_eq!(request, ControlRequest::Status);
+
+        let line = serde_json::to_string(&ControlRequest::Reload).unwrap();
+        assert_eq!(line, r#"{"command":"reload"}"#);
+
+        let request: ControlRequest = serde_json::from_str(r#"{"command":"pause"}"#).unwrap();
+        assert_eq!(request, ControlRequest::Pause { minutes: None });
+
+        assert!(serde_json::from_str::<ControlRequest>(r#"{"command":"shutdown"}"#).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_round_trip_over_socket() {
+        let path = std::env::temp_dir().join(format!("test-control-{}.sock", std::process::id()));
+        let (calls, requests) = mpsc::channel();
+        serve(&path, calls).unwrap();
+
+        // 模拟守护循环：收到请求后原样回显命令名
+        std::thread::spawn(move || {
+            for call in requests {
+                let command = serde_json::to_value(&call.request).unwrap();
+                let _ = call.reply.send(ControlResponse::success(command));
+            }
+        });
+
+        let response = send(&path, &ControlRequest::Pause { minutes: Some(30) }).unwrap();
+        assert!(response.ok);
+        let data = response.data.unwrap();
+        assert_eq!(data["command"], "pause");
+        assert_eq!(data["minutes"], 30);
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            send(&path, &ControlRequest::Status),
+            Err(ControlError::Connect { .. })
+        ));
+    }
+}
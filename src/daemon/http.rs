@@ -0,0 +1,188 @@
+//! 内置 HTTP 服务
+//!
+//! - `GET /healthz`：健康检查，最近一次成功检查足够新且在线（或已暂停自动登录）时返回 200，否则返回 503
+//! - `GET /status`：守护进程状态和在线用户信息（JSON）
+//! - `GET /metrics`：Prometheus 指标
+
+use super::{Daemon, StatusSnapshot};
+use crate::core::network::OnlineUserInfo;
+use crate::error::{AppError, Result};
+use crate::metrics;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tiny_http::{Header, Method, Request, Response, Server};
+use tracing::{debug, info, warn};
+
+/// 健康检查允许的最长检查间隔下限（秒）
+const MIN_HEALTHY_CHECK_AGE: i64 = 60;
+
+/// 守护循环发布给 HTTP 服务的数据
+#[derive(Debug, Default, Clone, Serialize)]
+struct Published {
+    status: Option<StatusSnapshot>,
+    online_info: Option<OnlineUserInfo>,
+}
+
+pub(crate) struct HttpServer {
+    published: Arc<Mutex<Published>>,
+}
+
+impl HttpServer {
+    /// 在 `bind` 上启动 HTTP 服务
+    pub(crate) fn start(bind: SocketAddr) -> Result<Self> {
+        let server = Server::http(bind)
+            .map_err(|e| AppError::Other(format!("启动 HTTP 服务失败 {}: {}", bind, e)))?;
+        info!("HTTP 服务已启动: http://{}", bind);
+
+        let published = Arc::new(Mutex::new(Published::default()));
+        let shared = Arc::clone(&published);
+        thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let snapshot = shared.lock().unwrap().clone();
+                respond(request, &snapshot);
+            }
+        });
+
+        Ok(Self { published })
+    }
+
+    /// 发布守护进程的最新状态
+    pub(crate) fn publish(&self, daemon: &Daemon) {
+        let mut published = self.published.lock().unwrap();
+        published.status = Some(daemon.status());
+        published.online_info = daemon.online_info().cloned();
+    }
+}
+
+fn respond(request: Request, published: &Published) {
+    debug!("HTTP 请求: {} {}", request.method(), request.url());
+
+    let response = match (request.method(), path_of(request.url())) {
+        (Method::Get, "/healthz") => {
+            let (healthy, message) = health(published.status.as_ref(), Local::now());
+            text(if healthy { 200 } else { 503 }, &message)
+        }
+        (Method::Get, "/status") => match serde_json::to_string_pretty(published) {
+            Ok(body) => with_content_type(Response::from_string(body), "application/json"),
+            Err(e) => text(500, &e.to_string()),
+        },
+        (Method::Get, "/metrics") => with_content_type(
+            Response::from_string(
+                metrics::global().render(published.status.as_ref(), Local::now()),
+            ),
+            "text/plain; version=0.0.4; charset=utf-8",
+        ),
+        _ => text(404, "not found"),
+    };
+
+    if let Err(e) = request.respond(response) {
+        warn!("发送 HTTP 响应失败: {}", e);
+    }
+}
+
+/// 判断守护进程是否健康，返回 (是否健康, 说明)
+fn health(status: Option<&StatusSnapshot>, now: DateTime<Local>) -> (bool, String) {
+    let Some(status) = status else {
+        return (false, "尚未完成首次检查".to_string());
+    };
+
+    let max_age = (status.interval as i64 * 3).max(MIN_HEALTHY_CHECK_AGE);
+    match status.last_success_at {
+        Some(at) if (now - at).num_seconds() <= max_age => {}
+        Some(at) => {
+            return (
+                false,
+                format!(
+                    "最近 {} 秒内没有成功的检查（上次成功于 {}）",
+                    max_age,
+                    at.format("%Y-%m-%d %H:%M:%S")
+                ),
+            );
+        }
+        None => return (false, "还没有成功的检查".to_string()),
+    }
+
+    if status.idle {
+        return (true, "ok（空闲：当前网络不匹配任何网络配置）".to_string());
+    }
+    match (status.connected, status.paused) {
+        (Some(true), _) => (true, "ok".to_string()),
+        (_, true) => (true, "ok（自动登录已暂停）".to_string()),
+        _ => (false, "未连接校园网".to_string()),
+    }
+}
+
+/// 请求路径（去掉查询参数，如 `/healthz?nocache=1`）
+fn path_of(url: &str) -> &str {
+    url.split_once('?').map_or(url, |(path, _)| path)
+}
+
+fn text(status: u16, body: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+    with_content_type(
+        Response::from_string(format!("{}\n", body)).with_status_code(status),
+        "text/plain; charset=utf-8",
+    )
+}
+
+fn with_content_type<R: std::io::Read>(response: Response<R>, content_type: &str) -> Response<R> {
+    match Header::from_bytes("Content-Type", content_type) {
+        Ok(header) => response.with_header(header),
+        Err(()) => response,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn snapshot(connected: Option<bool>, paused: bool, success_ago: Option<i64>) -> StatusSnapshot {
+        let now = Local::now();
+        StatusSnapshot {
+            username: "12345678".to_string(),
+            connected,
+            paused,
+            pause_remaining_secs: None,
+            ip: None,
+            login_count: 0,
+            last_login_at: None,
+            last_check_at: Some(now),
+            last_success_at: success_ago.map(|secs| now - Duration::seconds(secs)),
+            last_error: None,
+            interval: 10,
+            current_outage: None,
+            profile: None,
+            idle: false,
+        }
+    }
+
+    #[test]
+    fn test_path_of() {
+        assert_eq!(path_of("/healthz"), "/healthz");
+        assert_eq!(path_of("/healthz?x=1"), "/healthz");
+        assert_eq!(path_of("/metrics?"), "/metrics");
+    }
+
+    #[test]
+    fn test_health() {
+        let now = Local::now();
+
+        assert!(!health(None, now).0);
+        assert!(health(Some(&snapshot(Some(true), false, Some(5))), now).0);
+        // 暂停期间未连接仍视为健康
+        assert!(health(Some(&snapshot(Some(false), true, Some(5))), now).0);
+        assert!(!health(Some(&snapshot(Some(false), false, Some(5))), now).0);
+        // 不在任何网络配置中时空闲，视为健康
+        let idle = StatusSnapshot {
+            idle: true,
+            ..snapshot(None, false, Some(5))
+        };
+        assert!(health(Some(&idle), now).0);
+        // 检查长时间没有成功
+        assert!(!health(Some(&snapshot(Some(true), false, Some(600))), now).0);
+        assert!(!health(Some(&snapshot(None, false, None)), now).0);
+    }
+}
@@ -0,0 +1,172 @@
+use crate::error::LockError;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use tracing::{debug, info, warn};
+
+const LOCK_FILE_NAME: &str = "shu-net-keeper.lock";
+
+/// 锁文件中记录的持有者信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockHolder {
+    pub pid: u32,
+    pub program: String,
+    pub started_at: DateTime<Local>,
+}
+
+impl fmt::Display for LockHolder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (PID {}，启动于 {})",
+            self.program,
+            self.pid,
+            self.started_at.format("%Y-%m-%d %H:%M:%S")
+        )
+    }
+}
+
+/// 单实例锁
+/// 基于操作系统的文件锁，持有进程退出（包括崩溃）后由系统自动释放，
+/// 因此残留的锁文件不会阻止新实例启动。
+/// 锁文件从不删除：删除后其他进程可能锁住已删除的旧文件，而另一个进程在同一路径创建新文件，
+/// 两者会同时认为自己持有锁。
+#[derive(Debug)]
+pub struct InstanceLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl InstanceLock {
+    /// 获取实例锁，已有其他实例持有时返回 `LockError::AlreadyRunning`
+    pub fn acquire(path: &Path) -> Result<Self, LockError> {
+        let io_error = |e: std::io::Error| LockError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        };
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .map_err(io_error)?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let holder = read_holder(&mut file)
+                    .map(|h| h.to_string())
+                    .unwrap_or_else(|| "未知进程".to_string());
+                return Err(LockError::AlreadyRunning { holder });
+            }
+            Err(TryLockError::Error(e)) => return Err(io_error(e)),
+        }
+
+        if let Some(stale) = read_holder(&mut file) {
+            warn!("检测到过期的锁文件（{} 已退出），已接管", stale);
+        }
+
+        let holder = LockHolder {
+            pid: process::id(),
+            program: program_name(),
+            started_at: Local::now(),
+        };
+        let content = serde_json::to_string(&holder).map_err(std::io::Error::other);
+        content
+            .and_then(|content| {
+                file.set_len(0)?;
+                file.seek(SeekFrom::Start(0))?;
+                file.write_all(content.as_bytes())?;
+                file.sync_all()
+            })
+            .map_err(io_error)?;
+
+        info!("已获取实例锁: {}", path.display());
+        Ok(Self {
+            file,
+            path: path.to_path_buf(),
+        })
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        // 只清空持有者信息，保留文件；正常退出后不会被误报为过期的锁
+        let _ = self.file.set_len(0);
+        let _ = self.file.unlock();
+        debug!("已释放实例锁: {}", self.path.display());
+    }
+}
+
+/// 读取锁文件中的持有者信息（文件为空或内容损坏时返回 None）
+fn read_holder(file: &mut File) -> Option<LockHolder> {
+    let mut content = String::new();
+    file.seek(SeekFrom::Start(0)).ok()?;
+    file.read_to_string(&mut content).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn program_name() -> String {
+    env::current_exe()
+        .ok()
+        .and_then(|p| p.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "shu-net-keeper".to_string())
+}
+
+/// 获取锁文件路径
+/// 放在运行时目录中，保证 CLI 与 GUI 使用同一个锁
+pub fn get_lock_path() -> PathBuf {
+    super::get_runtime_dir().join(LOCK_FILE_NAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_lock_is_exclusive() {
+        let path = PathBuf::from("test_instance.lock");
+
+        let lock = InstanceLock::acquire(&path).unwrap();
+        match InstanceLock::acquire(&path) {
+            Err(LockError::AlreadyRunning { holder, .. }) => {
+                assert!(holder.contains(&format!("PID {}", process::id())));
+            }
+            other => panic!("期望 AlreadyRunning，实际为 {:?}", other),
+        }
+
+        drop(lock);
+        // 锁文件保留，只清空持有者信息
+        assert_eq!(fs::read_to_string(&path).unwrap(), "");
+
+        let lock = InstanceLock::acquire(&path).unwrap();
+        drop(lock);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_stale_lock_file_is_taken_over() {
+        let path = PathBuf::from("test_stale_instance.lock");
+        let stale = LockHolder {
+            pid: u32::MAX,
+            program: "shu-net-keeper".to_string(),
+            started_at: Local::now(),
+        };
+        fs::write(&path, serde_json::to_string(&stale).unwrap()).unwrap();
+
+        let lock = InstanceLock::acquire(&path).unwrap();
+        let holder: LockHolder = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(holder.pid, process::id());
+
+        drop(lock);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,683 @@
+pub mod control;
+mod http;
+mod lock;
+mod profile;
+mod state;
+mod stats;
+mod systemd;
+mod watch;
+
+pub use lock::{InstanceLock, LockHolder, get_lock_path};
+pub use profile::select_profile;
+pub use state::{DaemonState, Pause};
+pub use stats::{Outage, OutageLog, StatsSummary};
+
+use crate::config::{self, APPConfigValidated, ProfileValidated};
+use crate::constants::MAX_PAUSE_MINUTES;
+use crate::core::hooks::{HookContext, HookEvent};
+use crate::core::network::OnlineUserInfo;
+use crate::error::{AppError, EmailResult, LockError, LoginError, ValidationError};
+use crate::{core, error::Result, metrics};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use std::{env, fs, io};
+use tracing::{error, info, warn};
+
+const STATE_FILE_NAME: &str = "state.json";
+const STATS_FILE_NAME: &str = "stats.json";
+
+/// 单次检查的结果
+#[derive(Debug)]
+pub enum CheckOutcome {
+    /// 已在线，无需登录
+    Online,
+
+    /// 本次检查中完成了登录
+    LoggedIn {
+        ip: String,
+        ip_changed: bool,
+        /// 邮件通知结果（未启用 SMTP 时为 None）
+        notification: Option<EmailResult<()>>,
+    },
+
+    /// 未登录，但自动登录已暂停
+    Paused,
+
+    /// 当前网络不匹配任何网络配置，跳过检查
+    Idle,
+}
+
+/// 守护进程状态快照，供控制接口查询
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusSnapshot {
+    pub username: String,
+    /// 当前是否在线（尚未检查过时为 None）
+    pub connected: Option<bool>,
+    pub paused: bool,
+    /// 定时暂停的剩余秒数（未暂停或暂停直到手动恢复时为 None）
+    pub pause_remaining_secs: Option<i64>,
+    pub ip: Option<String>,
+    pub login_count: u32,
+    pub last_login_at: Option<DateTime<Local>>,
+    pub last_check_at: Option<DateTime<Local>>,
+    /// 上次检查成功（未出错）的时间
+    pub last_success_at: Option<DateTime<Local>>,
+    pub last_error: Option<String>,
+    pub interval: u64,
+    pub current_outage: Option<Outage>,
+    /// 当前匹配的网络配置（未配置 [[profiles]] 时为 None）
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// 当前网络不匹配任何网络配置，守护进程空闲
+    #[serde(default)]
+    pub idle: bool,
+}
+
+/// 守护进程引擎
+/// 负责一次次的检查与登录、触发钩子、记录断网统计，并持久化状态。
+/// CLI 与 GUI 的守护循环都通过它执行实际工作。
+pub struct Daemon {
+    config: APPConfigValidated,
+    state: DaemonState,
+    state_path: PathBuf,
+    outages: OutageLog,
+    stats_path: PathBuf,
+    /// 最近一次查询到的在线用户信息
+    online_info: Option<OnlineUserInfo>,
+    last_check_at: Option<DateTime<Local>>,
+    last_success_at: Option<DateTime<Local>>,
+    /// 当前匹配的网络配置名称
+    profile: Option<String>,
+    /// 当前网络不匹配任何网络配置
+    idle: bool,
+    /// 已触发 offline 钩子、尚未触发对应的 online 钩子
+    /// 启动后首次检查就离线时不触发 offline，恢复时也不触发 online
+    offline_notified: bool,
+    /// 尚未结束的钩子线程
+    pending_hooks: Vec<JoinHandle<()>>,
+    /// 单实例锁，随引擎一起释放
+    _lock: InstanceLock,
+}
+
+impl Daemon {
+    /// 创建引擎，并从 `data_dir` 中加载持久化的状态和断网记录
+    /// 已有其他实例（CLI 或 GUI）在运行时返回错误
+    pub fn new(config: APPConfigValidated, data_dir: &Path) -> Result<Self> {
+        prepare_runtime_dir().map_err(|e| LockError::Io {
+            path: get_runtime_dir().display().to_string(),
+            message: e.to_string(),
+        })?;
+        let lock = InstanceLock::acquire(&get_lock_path())?;
+
+        let state_path = get_state_path(data_dir);
+        let stats_path = data_dir.join(STATS_FILE_NAME);
+        info!("状态文件路径: {}", state_path.display());
+
+        let mut state = DaemonState::load(&state_path);
+        state.switch_account(&config.username);
+        save_state(&state, &state_path);
+
+        Ok(Self {
+            config,
+            state,
+            state_path,
+            outages: OutageLog::load(&stats_path),
+            stats_path,
+            online_info: None,
+            last_check_at: None,
+            last_success_at: None,
+            profile: None,
+            idle: false,
+            offline_notified: false,
+            pending_hooks: Vec::new(),
+            _lock: lock,
+        })
+    }
+
+    pub fn config(&self) -> &APPConfigValidated {
+        &self.config
+    }
+
+    pub fn state(&self) -> &DaemonState {
+        &self.state
+    }
+
+    pub fn online_info(&self) -> Option<&OnlineUserInfo> {
+        self.online_info.as_ref()
+    }
+
+    /// 当前匹配的网络配置
+    pub fn active_profile(&self) -> Option<&ProfileValidated> {
+        let name = self.profile.as_deref()?;
+        self.config.profiles.iter().find(|p| p.name == name)
+    }
+
+    /// 当前网络是否不匹配任何网络配置（空闲时不检查网络）
+    pub fn is_idle(&self) -> bool {
+        self.idle
+    }
+
+    /// 检查间隔：匹配的网络配置设置了 interval 时优先使用
+    pub fn interval(&self) -> u64 {
+        self.active_profile()
+            .and_then(|p| p.interval)
+            .unwrap_or(self.config.interval)
+    }
+
+    /// 自动登录是否处于暂停状态（暂停期间仍然检查网络状态）
+    pub fn is_paused(&self) -> bool {
+        self.state.is_paused(Local::now())
+    }
+
+    /// 当前状态快照
+    pub fn status(&self) -> StatusSnapshot {
+        StatusSnapshot {
+            username: self.config.username.clone(),
+            connected: self.state.connected,
+            paused: self.is_paused(),
+            pause_remaining_secs: self
+                .state
+                .pause_remaining(Local::now())
+                .map(|d| d.num_seconds()),
+            ip: self.state.last_ip_address.clone(),
+            login_count: self.state.login_count,
+            last_login_at: self.state.last_login_at,
+            last_check_at: self.last_check_at,
+            last_success_at: self.last_success_at,
+            last_error: self.state.last_error.clone(),
+            interval: self.interval(),
+            current_outage: self.outages.current().cloned(),
+            profile: self.profile.clone(),
+            idle: self.idle,
+        }
+    }
+
+    /// 暂停自动登录，`minutes` 为 None 时暂停直到手动恢复
+    /// 暂停状态会持久化，重启后仍然有效；分钟数来自控制请求，超出范围时拒绝
+    pub fn pause(&mut self, minutes: Option<u64>) -> Result<()> {
+        let in_range = minutes.is_none_or(|m| (1..=MAX_PAUSE_MINUTES).contains(&m));
+        if !in_range || !self.state.pause(minutes, Local::now()) {
+            return Err(ValidationError::InvalidPauseMinutes(minutes.unwrap_or_default()).into());
+        }
+        match minutes {
+            Some(m) => info!("已暂停自动登录 {} 分钟", m),
+            None => info!("已暂停自动登录，直到手动恢复"),
+        }
+        save_state(&self.state, &self.state_path);
+        Ok(())
+    }
+
+    /// 恢复自动登录
+    pub fn resume(&mut self) {
+        if self.state.resume() {
+            info!("已恢复自动登录");
+            save_state(&self.state, &self.state_path);
+        }
+    }
+
+    /// 替换配置（账号变化时重置登录统计）
+    pub fn set_config(&mut self, config: APPConfigValidated) {
+        let previous_state = self.state.clone();
+        self.state.switch_account(&config.username);
+        if self.state != previous_state {
+            save_state(&self.state, &self.state_path);
+        }
+        self.config = config;
+    }
+
+    /// 执行一次检查，必要时登录，并持久化变化的状态
+    pub fn check(&mut self) -> Result<CheckOutcome> {
+        let result = self.persist_after(Self::check_and_handle_network);
+        metrics::global().record_check(match &result {
+            Ok(CheckOutcome::Online) => "online",
+            Ok(CheckOutcome::LoggedIn { .. }) => "logged_in",
+            Ok(CheckOutcome::Paused) => "paused",
+            Ok(CheckOutcome::Idle) => "idle",
+            Err(_) => "error",
+        });
+        result
+    }
+
+    /// 立即登录（不检查当前是否在线，也不受暂停影响）
+    pub fn login(&mut self) -> Result<CheckOutcome> {
+        self.persist_after(Self::login_and_handle)
+    }
+
+    /// 执行检查或登录，并在状态变化时持久化
+    fn persist_after(
+        &mut self,
+        action: fn(&mut Self) -> Result<CheckOutcome>,
+    ) -> Result<CheckOutcome> {
+        let previous_state = self.state.clone();
+        let previous_outages = self.outages.outages.len();
+        let previous_open = self.outages.current().is_some();
+
+        let result = action(self);
+        let now = Local::now();
+        self.last_check_at = Some(now);
+
+        match &result {
+            Ok(_) => {
+                self.state.last_error = None;
+                self.last_success_at = Some(now);
+            }
+            Err(e) => self.state.last_error = Some(e.to_string()),
+        }
+
+        if self.state != previous_state {
+            save_state(&self.state, &self.state_path);
+        }
+        if (self.outages.outages.len() != previous_outages
+            || self.outages.current().is_some() != previous_open)
+            && let Err(e) = self.outages.save(&self.stats_path)
+        {
+            warn!("保存统计文件失败 {}: {}", self.stats_path.display(), e);
+        }
+
+        result
+    }
+
+    /// 检查网络并处理登录
+    fn check_and_handle_network(&mut self) -> Result<CheckOutcome> {
+        if self.state.expire_pause(Local::now()) {
+            info!("暂停已到期，恢复自动登录");
+        }
+
+        if !self.select_profile() {
+            return Ok(CheckOutcome::Idle);
+        }
+
+        // 1. 检查网络连接（已连接时顺带初始化 ip_status）
+        match core::network::check_network_connection(&mut self.state.last_ip_address) {
+            Ok(Some(info)) => {
+                self.online_info = Some(info);
+                self.mark_online(false);
+                return Ok(CheckOutcome::Online);
+            }
+            Ok(None) => self.mark_offline("未登录校园网".to_string(), None),
+            Err(e) => {
+                self.mark_offline(e.to_string(), Some(e.to_string()));
+                return Err(e.into());
+            }
+        }
+
+        if self.is_paused() {
+            info!("自动登录已暂停，跳过登录");
+            return Ok(CheckOutcome::Paused);
+        }
+
+        // 2. 网络未连接，尝试登录
+        warn!("网络未连接，尝试登录...");
+        self.login_and_handle()
+    }
+
+    /// 按当前网络选择网络配置，返回是否需要检查网络（未配置网络配置时总是需要）
+    fn select_profile(&mut self) -> bool {
+        let selected = if self.config.profiles.is_empty() {
+            None
+        } else {
+            select_profile(&self.config.profiles).map(|p| p.name.clone())
+        };
+        let idle = !self.config.profiles.is_empty() && selected.is_none();
+
+        if selected != self.profile || idle != self.idle {
+            match &selected {
+                Some(name) => info!("当前网络匹配网络配置: {}", name),
+                None if idle => info!("当前网络不匹配任何网络配置，进入空闲状态"),
+                None => {}
+            }
+        }
+        if idle && !self.idle {
+            // 离开校园网不算断网：丢弃未恢复的断网记录，回来时也不触发 online 钩子
+            self.outages.discard_current();
+            self.online_info = None;
+            self.state.connected = None;
+            self.offline_notified = false;
+        }
+
+        self.profile = selected;
+        self.idle = idle;
+        !idle
+    }
+
+    /// 登录并处理登录后的状态更新、钩子和通知
+    fn login_and_handle(&mut self) -> Result<CheckOutcome> {
+        if let Err(e) = core::login::network_login(&self.config.username, &self.config.password) {
+            self.fire_hook(
+                HookEvent::LoginFailure,
+                HookContext {
+                    error: Some(e.to_string()),
+                    ..self.hook_context()
+                },
+            );
+            return Err(e.into());
+        }
+        info!("✓ 登录成功");
+
+        // 3. 获取当前 IP
+        let current_ip = get_current_ip();
+
+        // 4. 检测 IP 变化（last_ip_address 为 None 表示首次登录，不视为 IP 变化）
+        let ip_changed = matches!(&self.state.last_ip_address, Some(old) if old != &current_ip);
+        let old_ip = self.state.last_ip_address.clone();
+
+        // 5. 更新状态（先于邮件通知，避免通知失败时丢失登录记录）
+        self.state.record_login(&current_ip);
+
+        // 6. 触发钩子
+        self.fire_hook(HookEvent::LoginSuccess, self.hook_context());
+        if ip_changed {
+            metrics::global().record_ip_change();
+            self.fire_hook(
+                HookEvent::IpChanged,
+                HookContext {
+                    old_ip,
+                    ..self.hook_context()
+                },
+            );
+        }
+        self.mark_online(true);
+
+        // 7. 发送邮件通知
+        let notification =
+            self.config.smtp.as_ref().map(|smtp| {
+                send_notification(smtp, &self.config.username, &current_ip, ip_changed)
+            });
+
+        Ok(CheckOutcome::LoggedIn {
+            ip: current_ip,
+            ip_changed,
+            notification,
+        })
+    }
+
+    /// 记录在线状态：结束断网记录，从离线恢复时触发 online 钩子（与 offline 钩子成对）
+    fn mark_online(&mut self, auto_recovered: bool) {
+        self.outages.end(Local::now(), auto_recovered);
+
+        if self.state.connected == Some(false) {
+            info!("网络已恢复");
+        }
+        if std::mem::take(&mut self.offline_notified) {
+            self.fire_hook(HookEvent::Online, self.hook_context());
+        }
+        self.state.connected = Some(true);
+    }
+
+    /// 记录离线状态：开始断网记录，从在线断开时触发 offline 钩子
+    fn mark_offline(&mut self, cause: String, error: Option<String>) {
+        self.outages.begin(&cause, Local::now());
+        self.online_info = None;
+
+        if self.state.connected == Some(true) {
+            warn!("网络已断开");
+            self.fire_hook(
+                HookEvent::Offline,
+                HookContext {
+                    error,
+                    ..self.hook_context()
+                },
+            );
+            self.offline_notified = true;
+        }
+        self.state.connected = Some(false);
+    }
+
+    fn hook_context(&self) -> HookContext {
+        HookContext {
+            username: self.config.username.clone(),
+            ip: self.state.last_ip_address.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn fire_hook(&mut self, event: HookEvent, context: HookContext) {
+        self.pending_hooks.retain(|handle| !handle.is_finished());
+        self.pending_hooks
+            .extend(core::hooks::dispatch(&self.config.hooks, event, context));
+    }
+
+    /// 等待已触发的钩子执行完毕（最长为钩子超时时间）
+    /// 单次执行的命令在退出前调用，否则后台线程中的钩子会随进程退出而中断
+    pub fn wait_for_hooks(&mut self) {
+        for handle in self.pending_hooks.drain(..) {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// 注销当前在线会话
+/// 守护进程未暂停时会在下一次检查时重新登录
+pub fn logout() -> Result<()> {
+    let info = core::network::query_online_info()?;
+    let user_index = match (&info.user_ip, &info.user_index) {
+        (Some(_), Some(index)) => index.clone(),
+        (None, _) => return Err(AppError::Other("当前未登录校园网".to_string())),
+        (Some(_), None) => {
+            return Err(LoginError::ResponseParse("在线信息中缺少 userIndex".to_string()).into());
+        }
+    };
+    core::login::network_logout(&user_index)?;
+    Ok(())
+}
+
+/// 获取数据目录（状态文件、统计文件所在目录，与实际使用的配置文件同目录）
+/// 配置文件可能来自 --config、用户配置目录或 /etc，数据跟随配置，而不是放在程序所在目录
+pub fn get_data_dir() -> PathBuf {
+    config::get_config_path()
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .map_or_else(|| PathBuf::from("."), Path::to_path_buf)
+}
+
+/// 获取状态文件路径
+pub fn get_state_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(STATE_FILE_NAME)
+}
+
+/// 获取统计文件路径
+pub fn get_stats_path(data_dir: &Path) -> PathBuf {
+    data_dir.join(STATS_FILE_NAME)
+}
+
+/// 获取运行时目录（锁文件、控制套接字所在目录）
+/// 固定为系统临时目录下当前用户专属的目录，不随 XDG_RUNTIME_DIR 变化，
+/// 保证 systemd 服务、终端中的 CLI 与桌面 GUI 使用同一把锁和同一个套接字
+fn get_runtime_dir() -> PathBuf {
+    // SAFETY: geteuid 没有前置条件，总是成功
+    #[cfg(unix)]
+    let name = format!("shu-net-keeper-{}", unsafe { libc::geteuid() });
+    #[cfg(not(unix))]
+    let name = "shu-net-keeper".to_string();
+    env::temp_dir().join(name)
+}
+
+/// 创建运行时目录
+/// Unix 上目录权限为 0700，且必须是当前用户所有的真实目录，防止其他用户抢先创建后替换锁文件或套接字
+fn prepare_runtime_dir() -> io::Result<()> {
+    let dir = get_runtime_dir();
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+
+        match fs::DirBuilder::new().mode(0o700).create(&dir) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e),
+        }
+        // 不跟随符号链接
+        let metadata = fs::symlink_metadata(&dir)?;
+        // SAFETY: 同上
+        let uid = unsafe { libc::geteuid() };
+        if !metadata.is_dir() || metadata.uid() != uid {
+            return Err(io::Error::other(format!(
+                "{} 不是当前用户的目录",
+                dir.display()
+            )));
+        }
+        if metadata.mode() & 0o077 != 0 {
+            fs::set_permissions(&dir, fs::Permissions::from_mode(0o700))?;
+        }
+    }
+    #[cfg(not(unix))]
+    fs::create_dir_all(&dir)?;
+
+    Ok(())
+}
+
+/// 守护进程主循环
+pub fn run(config: APPConfigValidated) -> Result<()> {
+    let mut daemon = Daemon::new(config, &get_data_dir())?;
+    let (calls, requests) = mpsc::channel();
+    control::serve_default(calls.clone());
+    let _watcher = watch::watch_config(&config::get_config_path(), calls);
+    let http = match &daemon.config().http {
+        Some(http) => Some(http::HttpServer::start(http.bind)?),
+        None => None,
+    };
+
+    // 以看门狗超时的一半作为心跳间隔
+    let heartbeat = systemd::watchdog_interval().map(|timeout| timeout / 2);
+    if let Some(heartbeat) = heartbeat {
+        info!(
+            "已启用 systemd 看门狗，心跳间隔 {} 秒",
+            heartbeat.as_secs_f32()
+        );
+    }
+    systemd::ready();
+
+    loop {
+        info!("正在检查网络连接状态...");
+
+        match daemon.check() {
+            Ok(CheckOutcome::Paused) => info!("自动登录已暂停，当前未登录"),
+            Ok(CheckOutcome::Idle) => info!("当前网络不匹配任何网络配置，空闲中"),
+            Ok(_) => info!("✓ 网络连接正常"),
+            Err(e) => error!("处理网络状态失败: {}", e),
+        }
+        publish(&daemon, http.as_ref());
+        systemd::watchdog();
+
+        let interval = daemon.interval();
+        info!("等待 {} 秒后再次检查...\n", interval);
+        wait_for_next_check(&mut daemon, &requests, http.as_ref(), heartbeat);
+    }
+}
+
+/// 等待到下一次检查，期间处理控制请求（包括配置文件变化触发的重新加载）
+/// 并按 `heartbeat` 发送看门狗心跳。手动触发检查或登录后重新计时，
+/// 检查间隔在重新加载后立即按新值计算
+fn wait_for_next_check(
+    daemon: &mut Daemon,
+    requests: &Receiver<control::ControlCall>,
+    http: Option<&http::HttpServer>,
+    heartbeat: Option<Duration>,
+) {
+    let mut started = Instant::now();
+    loop {
+        let deadline = started + Duration::from_secs(daemon.interval());
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return;
+        }
+        let timeout = heartbeat.map_or(remaining, |h| h.min(remaining));
+
+        // run() 持有发送端，通道不会断开，超时与断开同样继续等待
+        if let Ok(call) = requests.recv_timeout(timeout) {
+            if call.resets_timer() {
+                started = Instant::now();
+            }
+            control::handle(daemon, call);
+            publish(daemon, http);
+        }
+        systemd::watchdog();
+    }
+}
+
+/// 把最新状态发布给 systemd 和 HTTP 服务
+fn publish(daemon: &Daemon, http: Option<&http::HttpServer>) {
+    systemd::status(&status_line(daemon));
+    if let Some(http) = http {
+        http.publish(daemon);
+    }
+}
+
+/// systemd 状态文本（systemctl status 中显示）
+fn status_line(daemon: &Daemon) -> String {
+    let state = daemon.state();
+    let paused = if daemon.is_paused() {
+        "，自动登录已暂停"
+    } else {
+        ""
+    };
+    if daemon.is_idle() {
+        return "空闲：当前网络不匹配任何网络配置".to_string();
+    }
+    match (&state.last_error, state.connected) {
+        (Some(e), _) => format!("检查失败: {}", e),
+        (None, Some(true)) => format!(
+            "已连接，IP: {}{}",
+            state.last_ip_address.as_deref().unwrap_or("未知"),
+            paused
+        ),
+        (None, _) => format!("未连接{}", paused),
+    }
+}
+
+/// 保存状态文件，失败时仅记录警告，不影响主循环
+fn save_state(state: &DaemonState, path: &Path) {
+    if let Err(e) = state.save(path) {
+        warn!("保存状态文件失败 {}: {}", path.display(), e);
+    }
+}
+
+fn get_current_ip() -> String {
+    core::network::get_host_ip()
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| {
+            warn!("获取主机 IP 失败，使用默认值");
+            "未知".to_string()
+        })
+}
+
+fn send_notification(
+    smtp: &crate::config::SmtpConfigValidated,
+    username: &str,
+    ip: &str,
+    ip_changed: bool,
+) -> EmailResult<()> {
+    info!("准备发送登录通知邮件...");
+    core::email::send_login_notification(smtp, username, ip, ip_changed).map_err(|e| {
+        error!("✗ 邮件发送失败: {}", e);
+        e
+    })?;
+    info!("✓ 邮件通知发送成功");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn test_ip_changed_logic() {
+        let current_ip = "192.168.1.1".to_string();
+
+        // 首次登录（None）：不应视为 IP 变化
+        let last: Option<String> = None;
+        assert!(!matches!(&last, Some(old) if old != &current_ip));
+
+        // 相同 IP：不应视为 IP 变化
+        let last = Some("192.168.1.1".to_string());
+        assert!(!matches!(&last, Some(old) if old != &current_ip));
+
+        // IP 变化：应视为 IP 变化
+        let last = Some("10.0.0.1".to_string());
+        assert!(matches!(&last, Some(old) if old != &current_ip));
+    }
+}
@@ -0,0 +1,91 @@
+//! 按当前网络选择网络配置
+//!
+//! 按配置文件中的顺序检查，第一个匹配的配置生效；没有任何条件的配置总是匹配，可放在最后兜底。
+
+use crate::config::ProfileValidated;
+use crate::core::netenv::{self, Detect, NetworkEnvironment};
+
+/// 检测当前网络并选择配置，都不匹配时返回 None
+pub fn select_profile(profiles: &[ProfileValidated]) -> Option<&ProfileValidated> {
+    let env = netenv::detect(Detect {
+        gateways: profiles.iter().any(|p| !p.gateway.is_empty()),
+        ssids: profiles.iter().any(|p| !p.ssid.is_empty()),
+    });
+    select_in(profiles, &env)
+}
+
+fn select_in<'a>(
+    profiles: &'a [ProfileValidated],
+    env: &NetworkEnvironment,
+) -> Option<&'a ProfileValidated> {
+    profiles.iter().find(|profile| matches(profile, env))
+}
+
+/// 指定的条件全部满足时匹配，同一条件中的多个值满足其一即可
+fn matches(profile: &ProfileValidated, env: &NetworkEnvironment) -> bool {
+    condition(&profile.ssid, |ssid| env.ssids.contains(ssid))
+        && condition(&profile.interface, |name| env.interfaces.contains(name))
+        && condition(&profile.subnet, |net| {
+            env.addresses.iter().any(|addr| net.contains(addr))
+        })
+        && condition(&profile.gateway, |gateway| env.gateways.contains(gateway))
+}
+
+/// 未指定的条件（值为空）视为满足
+fn condition<T>(values: &[T], hit: impl FnMut(&T) -> bool) -> bool {
+    values.is_empty() || values.iter().any(hit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str) -> ProfileValidated {
+        ProfileValidated {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_select_profile() {
+        let dorm = ProfileValidated {
+            interface: vec!["eth0".to_string()],
+            subnet: vec!["10.10.0.0/16".parse().unwrap()],
+            ..profile("dorm")
+        };
+        let wifi = ProfileValidated {
+            ssid: vec!["Shu(ForAll)".to_string(), "SHU-WiFi".to_string()],
+            interval: Some(30),
+            ..profile("wifi")
+        };
+        let profiles = vec![dorm, wifi];
+
+        let wired = NetworkEnvironment {
+            interfaces: vec!["eth0".to_string()],
+            addresses: vec!["10.10.1.2".parse().unwrap()],
+            ..Default::default()
+        };
+        assert_eq!(select_in(&profiles, &wired).unwrap().name, "dorm");
+
+        let campus_wifi = NetworkEnvironment {
+            interfaces: vec!["wlan0".to_string()],
+            ssids: vec!["SHU-WiFi".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(select_in(&profiles, &campus_wifi).unwrap().name, "wifi");
+
+        // 网卡匹配但不在校园网网段
+        let home = NetworkEnvironment {
+            interfaces: vec!["eth0".to_string()],
+            addresses: vec!["192.168.1.10".parse().unwrap()],
+            gateways: vec!["192.168.1.1".parse().unwrap()],
+            ..Default::default()
+        };
+        assert!(select_in(&profiles, &home).is_none());
+
+        // 没有条件的配置兜底
+        let fallback = [profile("any")];
+        assert_eq!(select_in(&fallback, &home).unwrap().name, "any");
+    }
+}
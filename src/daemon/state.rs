@@ -0,0 +1,239 @@
+use crate::fsutil::write_atomic;
+use chrono::{DateTime, Duration, Local};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+use tracing::{debug, info, warn};
+
+/// 守护进程状态
+/// 持久化到状态文件中，程序重启后恢复，保证 IP 变化检测和计数不会因重启而丢失
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DaemonState {
+    /// 上次记录的 IP 地址
+    pub last_ip_address: Option<String>,
+
+    /// 上次登录成功的时间
+    pub last_login_at: Option<DateTime<Local>>,
+
+    /// 累计登录成功次数
+    pub login_count: u32,
+
+    /// 最近一次错误信息（成功后清空）
+    pub last_error: Option<String>,
+
+    /// 当前使用的账号
+    pub active_account: Option<String>,
+
+    /// 自动登录暂停状态（None 表示未暂停）
+    pub pause: Option<Pause>,
+
+    /// 上次检查时是否在线（运行时字段，不持久化；None 表示尚未检查）
+    #[serde(skip)]
+    pub connected: Option<bool>,
+}
+
+/// 自动登录暂停方式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Pause {
+    /// 暂停到指定时间
+    Until(DateTime<Local>),
+
+    /// 暂停直到手动恢复
+    Indefinitely,
+}
+
+impl DaemonState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从状态文件加载，文件不存在或损坏时返回默认状态
+    pub fn load(path: &Path) -> Self {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                debug!("状态文件不存在，使用初始状态: {}", path.display());
+                return Self::new();
+            }
+            Err(e) => {
+                warn!("读取状态文件失败，使用初始状态: {}", e);
+                return Self::new();
+            }
+        };
+
+        match serde_json::from_str(&content) {
+            Ok(state) => {
+                debug!("已加载状态文件: {}", path.display());
+                state
+            }
+            Err(e) => {
+                warn!("状态文件已损坏，使用初始状态: {}", e);
+                Self::new()
+            }
+        }
+    }
+
+    /// 原子写入状态文件
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let content = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        write_atomic(path, content.as_bytes())
+    }
+
+    /// 切换当前账号；账号变化时重置与账号相关的记录
+    pub fn switch_account(&mut self, username: &str) {
+        if self.active_account.as_deref() == Some(username) {
+            return;
+        }
+
+        if let Some(old) = &self.active_account {
+            info!("账号已从 {} 切换为 {}，重置登录记录", old, username);
+            self.last_login_at = None;
+            self.login_count = 0;
+            self.last_error = None;
+        }
+
+        self.active_account = Some(username.to_string());
+    }
+
+    /// 记录一次登录成功
+    pub fn record_login(&mut self, ip: &str) {
+        self.last_ip_address = Some(ip.to_string());
+        self.last_login_at = Some(Local::now());
+        self.login_count += 1;
+    }
+
+    /// 暂停自动登录，`minutes` 为 None 时暂停直到手动恢复
+    /// 结束时间超出可表示的范围时不改变状态并返回 false
+    pub fn pause(&mut self, minutes: Option<u64>, now: DateTime<Local>) -> bool {
+        let pause = match minutes {
+            Some(minutes) => match pause_end(minutes, now) {
+                Some(until) => Pause::Until(until),
+                None => return false,
+            },
+            None => Pause::Indefinitely,
+        };
+        self.pause = Some(pause);
+        true
+    }
+
+    /// 恢复自动登录，返回之前是否处于暂停状态
+    pub fn resume(&mut self) -> bool {
+        self.pause.take().is_some()
+    }
+
+    /// 清除已到期的暂停，返回是否清除了暂停
+    pub fn expire_pause(&mut self, now: DateTime<Local>) -> bool {
+        match self.pause {
+            Some(Pause::Until(until)) if until <= now => {
+                self.pause = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn is_paused(&self, now: DateTime<Local>) -> bool {
+        match self.pause {
+            Some(Pause::Until(until)) => until > now,
+            Some(Pause::Indefinitely) => true,
+            None => false,
+        }
+    }
+
+    /// 定时暂停的剩余时间（未暂停或暂停直到手动恢复时为 None）
+    pub fn pause_remaining(&self, now: DateTime<Local>) -> Option<Duration> {
+        match self.pause {
+            Some(Pause::Until(until)) if until > now => Some(until - now),
+            _ => None,
+        }
+    }
+}
+
+/// 定时暂停的结束时间，全程使用不会溢出的计算
+fn pause_end(minutes: u64, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    let minutes = i64::try_from(minutes).ok()?;
+    now.checked_add_signed(Duration::try_minutes(minutes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_state_save_and_load() {
+        let path = PathBuf::from("test_state.json");
+
+        let mut state = DaemonState::new();
+        state.switch_account("12345678");
+        state.record_login("10.0.0.1");
+        state.last_error = Some("登录失败".to_string());
+        state.save(&path).unwrap();
+
+        let loaded = DaemonState::load(&path);
+        assert_eq!(loaded, state);
+        assert_eq!(loaded.login_count, 1);
+        assert_eq!(loaded.last_ip_address.as_deref(), Some("10.0.0.1"));
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_state_load_missing_or_corrupt() {
+        let missing = DaemonState::load(Path::new("test_state_missing.json"));
+        assert_eq!(missing, DaemonState::default());
+
+        let path = PathBuf::from("test_state_corrupt.json");
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(DaemonState::load(&path), DaemonState::default());
+
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_switch_account_resets_counters() {
+        let mut state = DaemonState::new();
+        state.switch_account("12345678");
+        state.record_login("10.0.0.1");
+
+        // 同一账号：保留记录
+        state.switch_account("12345678");
+        assert_eq!(state.login_count, 1);
+
+        // 切换账号：重置计数，但保留主机 IP
+        state.switch_account("87654321");
+        assert_eq!(state.login_count, 0);
+        assert!(state.last_login_at.is_none());
+        assert_eq!(state.last_ip_address.as_deref(), Some("10.0.0.1"));
+    }
+
+    #[test]
+    fn test_pause_until_and_expire() {
+        let now = Local::now();
+        let mut state = DaemonState::new();
+
+        assert!(state.pause(Some(30), now));
+        assert!(state.is_paused(now));
+        assert_eq!(state.pause_remaining(now), Some(Duration::minutes(30)));
+        assert!(!state.expire_pause(now + Duration::minutes(29)));
+
+        let later = now + Duration::minutes(31);
+        assert!(!state.is_paused(later));
+        assert!(state.expire_pause(later));
+        assert!(state.pause.is_none());
+
+        assert!(state.pause(None, now));
+        assert!(state.is_paused(later));
+        assert_eq!(state.pause_remaining(later), None);
+        assert!(state.resume());
+        assert!(!state.resume());
+
+        // 超出范围的时长不会溢出，也不改变状态
+        assert!(!state.pause(Some(u64::MAX), now));
+        assert!(!state.pause(Some(i64::MAX as u64), now));
+        assert!(state.pause.is_none());
+    }
+}
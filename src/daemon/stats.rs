@@ -0,0 +1,300 @@
+use crate::constants::STATS_RETENTION_DAYS;
+use crate::fsutil::write_atomic;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::Path;
+use tracing::{debug, warn};
+
+/// 一次断网记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Outage {
+    /// 断网开始时间
+    pub start: DateTime<Local>,
+
+    /// 恢复时间（None 表示仍未恢复）
+    pub end: Option<DateTime<Local>>,
+
+    /// 断网原因
+    pub cause: String,
+
+    /// 是否由自动登录恢复
+    pub auto_recovered: bool,
+}
+
+impl Outage {
+    /// 断网持续时长（未恢复时计算到 now）
+    fn duration(&self, now: DateTime<Local>) -> Duration {
+        self.end.unwrap_or(now) - self.start
+    }
+}
+
+/// 断网记录日志，持久化到统计文件
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutageLog {
+    pub outages: Vec<Outage>,
+}
+
+/// 某一时间段内的可用性统计
+#[derive(Debug, Clone, Serialize)]
+pub struct StatsSummary {
+    /// 时间段标签（日期或周起止日期）
+    pub label: String,
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+
+    /// 该时间段内开始的断网次数
+    pub outage_count: usize,
+
+    /// 其中由自动登录恢复的次数
+    pub auto_recovered_count: usize,
+
+    /// 断网总时长（秒）
+    pub downtime_secs: i64,
+
+    /// 可用率（0.0 ~ 1.0）
+    pub availability: f64,
+
+    /// 平均恢复时间（秒），没有已恢复的断网时为 None
+    pub mttr_secs: Option<i64>,
+}
+
+impl OutageLog {
+    /// 从统计文件加载，文件不存在或损坏时返回空记录
+    pub fn load(path: &Path) -> Self {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Self::default(),
+            Err(e) => {
+                warn!("读取统计文件失败: {}", e);
+                return Self::default();
+            }
+        };
+
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            warn!("统计文件已损坏，重新开始记录: {}", e);
+            Self::default()
+        })
+    }
+
+    /// 原子写入统计文件
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let content = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        write_atomic(path, content.as_bytes())
+    }
+
+    /// 当前未恢复的断网记录
+    pub fn current(&self) -> Option<&Outage> {
+        self.outages.last().filter(|o| o.end.is_none())
+    }
+
+    /// 开始一次断网记录；已有未恢复的记录时不重复记录
+    /// 返回是否新增了记录
+    pub fn begin(&mut self, cause: &str, at: DateTime<Local>) -> bool {
+        if self.current().is_some() {
+            return false;
+        }
+
+        debug!("记录断网开始: {}", cause);
+        self.outages.push(Outage {
+            start: at,
+            end: None,
+            cause: cause.to_string(),
+            auto_recovered: false,
+        });
+        self.prune(at);
+        true
+    }
+
+    /// 结束当前断网记录
+    /// 返回是否有记录被结束
+    pub fn end(&mut self, at: DateTime<Local>, auto_recovered: bool) -> bool {
+        match self.outages.last_mut() {
+            Some(outage) if outage.end.is_none() => {
+                debug!("记录断网恢复，自动登录恢复: {}", auto_recovered);
+                outage.end = Some(at);
+                outage.auto_recovered = auto_recovered;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 丢弃当前未恢复的断网记录（如离开校园网时），返回是否有记录被丢弃
+    pub fn discard_current(&mut self) -> bool {
+        if self.current().is_none() {
+            return false;
+        }
+        debug!("丢弃未恢复的断网记录");
+        self.outages.pop();
+        true
+    }
+
+    /// 删除超过保留期限的记录
+    fn prune(&mut self, now: DateTime<Local>) {
+        let cutoff = now - Duration::days(STATS_RETENTION_DAYS.into());
+        self.outages
+            .retain(|o| o.end.is_none_or(|end| end >= cutoff));
+    }
+
+    /// 统计 [start, end) 时间段内的可用性
+    pub fn summarize(
+        &self,
+        label: String,
+        start: DateTime<Local>,
+        end: DateTime<Local>,
+        now: DateTime<Local>,
+    ) -> StatsSummary {
+        // 未来的部分不计入统计
+        let end = end.min(now).max(start);
+        let window = (end - start).num_seconds();
+
+        let mut outage_count = 0;
+        let mut auto_recovered_count = 0;
+        let mut downtime_secs = 0;
+        let mut repair_secs = Vec::new();
+
+        for outage in &self.outages {
+            if outage.start >= start && outage.start < end {
+                outage_count += 1;
+                if outage.auto_recovered {
+                    auto_recovered_count += 1;
+                }
+            }
+
+            let overlap_start = outage.start.max(start);
+            let overlap_end = outage.end.unwrap_or(now).min(end);
+            if overlap_end > overlap_start {
+                downtime_secs += (overlap_end - overlap_start).num_seconds();
+            }
+
+            if let Some(recovered) = outage.end
+                && recovered >= start
+                && recovered < end
+            {
+                repair_secs.push(outage.duration(now).num_seconds());
+            }
+        }
+
+        let availability = if window > 0 {
+            1.0 - downtime_secs as f64 / window as f64
+        } else {
+            1.0
+        };
+
+        let mttr_secs = if repair_secs.is_empty() {
+            None
+        } else {
+            Some(repair_secs.iter().sum::<i64>() / repair_secs.len() as i64)
+        };
+
+        StatsSummary {
+            label,
+            start,
+            end,
+            outage_count,
+            auto_recovered_count,
+            downtime_secs,
+            availability,
+            mttr_secs,
+        }
+    }
+
+    /// 最近 `days` 天的按天统计（从今天开始倒序）
+    pub fn daily(&self, days: u32, now: DateTime<Local>) -> Vec<StatsSummary> {
+        let today = now.date_naive();
+        (0..days as i64)
+            .map(|i| {
+                let date = today - Duration::days(i);
+                self.summarize(
+                    date.format("%Y-%m-%d").to_string(),
+                    local_midnight(date),
+                    local_midnight(date + Duration::days(1)),
+                    now,
+                )
+            })
+            .collect()
+    }
+
+    /// 最近 `weeks` 周的按周统计（周一为一周开始，从本周开始倒序）
+    pub fn weekly(&self, weeks: u32, now: DateTime<Local>) -> Vec<StatsSummary> {
+        let today = now.date_naive();
+        let this_monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+        (0..weeks as i64)
+            .map(|i| {
+                let monday = this_monday - Duration::weeks(i);
+                let sunday = monday + Duration::days(6);
+                self.summarize(
+                    format!("{} ~ {}", monday.format("%m-%d"), sunday.format("%m-%d")),
+                    local_midnight(monday),
+                    local_midnight(monday + Duration::weeks(1)),
+                    now,
+                )
+            })
+            .collect()
+    }
+}
+
+fn local_midnight(date: NaiveDate) -> DateTime<Local> {
+    date.and_hms_opt(0, 0, 0)
+        .and_then(|dt| dt.and_local_timezone(Local).earliest())
+        .unwrap_or_else(Local::now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(h: u32, m: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 3, 10, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_begin_and_end_outage() {
+        let mut log = OutageLog::default();
+
+        assert!(log.begin("未登录校园网", at(10, 0)));
+        // 已有未恢复的断网时不重复记录
+        assert!(!log.begin("连接失败", at(10, 1)));
+        assert!(log.end(at(10, 5), true));
+        assert!(!log.end(at(10, 6), true));
+
+        assert_eq!(log.outages.len(), 1);
+        assert_eq!(log.outages[0].cause, "未登录校园网");
+        assert!(log.outages[0].auto_recovered);
+        assert!(log.current().is_none());
+    }
+
+    #[test]
+    fn test_summarize_window() {
+        let mut log = OutageLog::default();
+        log.begin("未登录校园网", at(10, 0));
+        log.end(at(10, 10), true);
+        log.begin("连接失败", at(11, 0));
+        log.end(at(11, 20), false);
+
+        let summary = log.summarize("test".to_string(), at(10, 0), at(12, 0), at(12, 0));
+
+        assert_eq!(summary.outage_count, 2);
+        assert_eq!(summary.auto_recovered_count, 1);
+        assert_eq!(summary.downtime_secs, 30 * 60);
+        assert_eq!(summary.mttr_secs, Some(15 * 60));
+        assert!((summary.availability - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_summarize_open_outage_counts_until_now() {
+        let mut log = OutageLog::default();
+        log.begin("连接失败", at(11, 0));
+
+        let summary = log.summarize("test".to_string(), at(10, 0), at(14, 0), at(12, 0));
+
+        // 窗口截止到 now，未恢复的断网计算到 now
+        assert_eq!(summary.downtime_secs, 60 * 60);
+        assert_eq!(summary.mttr_secs, None);
+        assert!((summary.availability - 0.5).abs() < 1e-9);
+    }
+}
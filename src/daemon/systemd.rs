@@ -0,0 +1,56 @@
+//! systemd 集成（sd_notify）
+//!
+//! 启用 `systemd` 特性并以 `Type=notify` 服务运行时，向 systemd 报告就绪状态、
+//! 当前连接状态（STATUS=）和看门狗心跳（WATCHDOG=1）。
+//! 未启用特性、非 Unix 平台或未由 systemd 启动（没有 NOTIFY_SOCKET）时，所有函数均为空操作。
+
+pub use imp::{ready, status, watchdog, watchdog_interval};
+
+#[cfg(all(feature = "systemd", unix))]
+mod imp {
+    use sd_notify::NotifyState;
+    use std::time::Duration;
+    use tracing::debug;
+
+    fn notify(state: &[NotifyState]) {
+        if let Err(e) = sd_notify::notify(false, state) {
+            debug!("发送 sd_notify 消息失败: {}", e);
+        }
+    }
+
+    /// 通知 systemd 服务已就绪（READY=1）
+    pub fn ready() {
+        notify(&[NotifyState::Ready]);
+    }
+
+    /// 更新 systemctl status 中显示的状态文本（STATUS=）
+    pub fn status(message: &str) {
+        notify(&[NotifyState::Status(message)]);
+    }
+
+    /// 发送看门狗心跳（WATCHDOG=1）
+    pub fn watchdog() {
+        notify(&[NotifyState::Watchdog]);
+    }
+
+    /// 服务配置了 WatchdogSec 时返回看门狗超时时间
+    pub fn watchdog_interval() -> Option<Duration> {
+        let mut usec = 0;
+        sd_notify::watchdog_enabled(false, &mut usec).then(|| Duration::from_micros(usec))
+    }
+}
+
+#[cfg(not(all(feature = "systemd", unix)))]
+mod imp {
+    use std::time::Duration;
+
+    pub fn ready() {}
+
+    pub fn status(_message: &str) {}
+
+    pub fn watchdog() {}
+
+    pub fn watchdog_interval() -> Option<Duration> {
+        None
+    }
+}
@@ -0,0 +1,137 @@
+//! 配置文件热重载
+//!
+//! 监听配置文件所在目录（编辑器和 `write_atomic` 都通过重命名替换文件，直接监听文件会丢失后续变化），
+//! 配置文件变化后向守护循环发送一次 `Reload` 请求，由守护循环重新加载并验证配置。
+
+use super::control::{ControlCall, ControlRequest};
+use notify::event::{AccessKind, AccessMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// 等待连续的文件事件结束后再重新加载（保存文件通常会产生多个事件）
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 配置文件监听器，释放时停止监听
+pub(crate) struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+/// 开始监听 `path`，失败时仅记录警告（仍可通过 `ctl reload` 重新加载）
+pub(crate) fn watch_config(path: &Path, calls: Sender<ControlCall>) -> Option<ConfigWatcher> {
+    match start(path, calls) {
+        Ok(watcher) => {
+            info!("正在监听配置文件变化: {}", path.display());
+            Some(watcher)
+        }
+        Err(e) => {
+            warn!("监听配置文件失败 {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn start(path: &Path, calls: Sender<ControlCall>) -> notify::Result<ConfigWatcher> {
+    let path = path.canonicalize()?;
+    let dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+
+    let (events, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(events)?;
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+
+    thread::spawn(move || forward_changes(&path, &receiver, &calls));
+    Ok(ConfigWatcher { _watcher: watcher })
+}
+
+/// 把配置文件的变化合并后转换为 Reload 请求，守护循环退出后结束
+fn forward_changes(
+    path: &Path,
+    events: &Receiver<notify::Result<Event>>,
+    calls: &Sender<ControlCall>,
+) {
+    while let Ok(event) = events.recv() {
+        if !is_config_change(&event, path) {
+            continue;
+        }
+
+        // 合并短时间内的后续事件
+        loop {
+            match events.recv_timeout(DEBOUNCE) {
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+
+        info!("检测到配置文件变化，正在重新加载");
+        if calls
+            .send(ControlCall::internal(ControlRequest::Reload))
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+fn is_config_change(event: &notify::Result<Event>, path: &Path) -> bool {
+    let event = match event {
+        Ok(event) => event,
+        Err(e) => {
+            warn!("配置文件监听出错: {}", e);
+            return false;
+        }
+    };
+
+    let relevant = matches!(
+        event.kind,
+        EventKind::Create(_)
+            | EventKind::Modify(_)
+            | EventKind::Access(AccessKind::Close(AccessMode::Write))
+    );
+    let matched = relevant && event.paths.iter().any(|p| same_file(p, path));
+    if matched {
+        debug!("配置文件事件: {:?}", event.kind);
+    }
+    matched
+}
+
+/// 事件中的路径可能未规范化（如 macOS 的 /var 与 /private/var），不相等时再比较规范化后的目录
+fn same_file(candidate: &Path, path: &Path) -> bool {
+    candidate == path
+        || (candidate.file_name() == path.file_name()
+            && candidate
+                .parent()
+                .and_then(|dir| dir.canonicalize().ok())
+                .as_deref()
+                == path.parent())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_reload_on_change() {
+        let dir = Path::new("test_watch_config");
+        fs::create_dir_all(dir).unwrap();
+        let path = dir.join("config.toml");
+        fs::write(&path, "interval = 10\n").unwrap();
+
+        let (calls, requests) = mpsc::channel();
+        let watcher = watch_config(&path, calls);
+        assert!(watcher.is_some());
+
+        // 同目录下的其他文件不触发重新加载
+        fs::write(dir.join("other.toml"), "x").unwrap();
+        assert!(requests.recv_timeout(DEBOUNCE * 3).is_err());
+
+        crate::fsutil::write_atomic(&path, b"interval = 20\n").unwrap();
+        let call = requests.recv_timeout(Duration::from_secs(5));
+        fs::remove_dir_all(dir).unwrap();
+        assert!(call.is_ok());
+    }
+}
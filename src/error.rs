@@ -1,3 +1,5 @@
+use serde::Serialize;
+use std::fmt;
 use thiserror::Error;
 
 /// 应用程序的统一错误类型
@@ -18,6 +20,15 @@ pub enum AppError {
     #[error("验证错误: {0}")]
     Validation(#[from] ValidationError),
 
+    #[error("钩子错误: {0}")]
+    Hook(#[from] HookError),
+
+    #[error("实例锁错误: {0}")]
+    Lock(#[from] LockError),
+
+    #[error("控制接口错误: {0}")]
+    Control(#[from] ControlError),
+
     #[error("IO 错误: {0}")]
     Io(#[from] std::io::Error),
 
@@ -28,20 +39,99 @@ pub enum AppError {
 /// 配置错误类型
 #[derive(Error, Debug)]
 pub enum ConfigError {
-    #[error("配置文件不存在: {path}\n请先运行 --init 创建配置文件")]
+    #[error("配置文件不存在: {path}\n请先运行 `shu-net-keeper init` 创建配置文件")]
     FileNotFound { path: String },
 
+    #[error(
+        "未找到配置文件，已搜索以下位置:\n{searched}\n请先运行 `shu-net-keeper init` 创建配置文件，或使用 --config 指定路径"
+    )]
+    NotFound { searched: String },
+
     #[error("读取配置文件失败: {0}")]
     ReadFailed(String),
 
     #[error("解析配置文件失败: {0}")]
     ParseFailed(String),
 
+    #[error("配置格式版本 {found} 高于当前程序支持的版本 {supported}，请升级 shu-net-keeper")]
+    UnsupportedVersion { found: u32, supported: u32 },
+
     #[error("配置验证失败: {0}")]
     ValidationFailed(String),
 
+    #[error("配置有 {} 处错误:\n{}", .issues.len(), format_issues(.issues))]
+    Invalid { issues: Vec<ConfigIssue> },
+
     #[error("SMTP 配置错误: {0}")]
     SmtpConfig(String),
+
+    #[error("钩子配置错误: {0}")]
+    HooksConfig(String),
+
+    #[error("HTTP 服务配置错误: {0}")]
+    HttpConfig(String),
+
+    #[error("网络配置错误: {0}")]
+    ProfileConfig(String),
+
+    #[error("读取密码失败: {0}")]
+    Secret(String),
+}
+
+/// 配置中的一处问题
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigIssue {
+    /// 字段路径，如 `smtp.sender`（语法错误等无法对应到字段时为 None）
+    pub field: Option<String>,
+    pub message: String,
+    /// 在配置文件中的位置（仅 TOML 配置文件）
+    pub location: Option<SourceLocation>,
+}
+
+/// 配置文件中的位置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SourceLocation {
+    /// 行号，从 1 开始
+    pub line: usize,
+    /// 列号（按字符计），从 1 开始
+    pub column: usize,
+    /// 所在行的内容
+    pub snippet: String,
+}
+
+impl ConfigIssue {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: Some(field.into()),
+            message: message.into(),
+            location: None,
+        }
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "{}: {}", field, self.message)?,
+            None => write!(f, "{}", self.message)?,
+        }
+        if let Some(location) = &self.location {
+            write!(
+                f,
+                "（第 {} 行第 {} 列）\n    {:>4} | {}",
+                location.line, location.column, location.line, location.snippet
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn format_issues(issues: &[ConfigIssue]) -> String {
+    issues
+        .iter()
+        .map(|issue| format!("  - {}", issue))
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
 /// 网络错误类型
@@ -85,6 +175,19 @@ pub enum LoginError {
     UrlParse(String),
 }
 
+impl LoginError {
+    /// 失败原因的简短标识，用于指标标签
+    pub fn reason(&self) -> &'static str {
+        match self {
+            LoginError::QueryString(_) => "query_string",
+            LoginError::Request(_) => "request",
+            LoginError::ResponseParse(_) => "response_parse",
+            LoginError::Authentication { .. } => "authentication",
+            LoginError::UrlParse(_) => "url_parse",
+        }
+    }
+}
+
 /// 邮件错误类型
 #[derive(Error, Debug)]
 pub enum EmailError {
@@ -104,6 +207,48 @@ pub enum EmailError {
     SendFailed(String),
 }
 
+/// 钩子命令错误类型
+#[derive(Error, Debug)]
+pub enum HookError {
+    #[error("启动命令失败: {0}")]
+    SpawnFailed(String),
+
+    #[error("命令执行超时（{} 秒）", .0.as_secs_f64())]
+    Timeout(std::time::Duration),
+
+    #[error("等待命令结束失败: {0}")]
+    WaitFailed(String),
+}
+
+/// 单实例锁错误类型
+#[derive(Error, Debug)]
+pub enum LockError {
+    #[error("已有实例正在运行: {holder}\n该进程退出后锁会由系统自动释放")]
+    AlreadyRunning { holder: String },
+
+    #[error("无法获取锁文件 {path}: {message}")]
+    Io { path: String, message: String },
+}
+
+/// 控制接口错误类型
+#[derive(Error, Debug)]
+pub enum ControlError {
+    #[error("无法连接守护进程 {path}: {message}\n请确认守护进程正在运行")]
+    Connect { path: String, message: String },
+
+    #[error("通信失败: {0}")]
+    Io(String),
+
+    #[error("响应格式错误: {0}")]
+    Protocol(String),
+
+    #[error("守护进程返回错误: {0}")]
+    Rejected(String),
+
+    #[error("当前平台不支持控制接口")]
+    Unsupported,
+}
+
 /// 数据验证错误类型
 #[derive(Error, Debug)]
 pub enum ValidationError {
@@ -113,6 +258,9 @@ pub enum ValidationError {
     #[error("用户名格式不正确: {0}")]
     InvalidUsername(String),
 
+    #[error("账号规则配置错误: {0}")]
+    InvalidAccountRule(String),
+
     #[error("端口号无效: {0}")]
     InvalidPort(u16),
 
@@ -121,6 +269,12 @@ pub enum ValidationError {
 
     #[error("字段不能为空: {0}")]
     EmptyField(String),
+
+    #[error("暂停时长无效: {0} 分钟（应为 1 到 {max} 分钟）", max = crate::constants::MAX_PAUSE_MINUTES)]
+    InvalidPauseMinutes(u64),
+
+    #[error("检查间隔无效: {0} 秒（应大于 0）")]
+    InvalidInterval(u64),
 }
 
 // ==================== From 转换实现 ====================
@@ -193,6 +347,12 @@ pub type LoginResult<T> = std::result::Result<T, LoginError>;
 /// 邮件相关的 Result 类型
 pub type EmailResult<T> = std::result::Result<T, EmailError>;
 
+/// 钩子相关的 Result 类型
+pub type HookResult<T> = std::result::Result<T, HookError>;
+
+/// 控制接口相关的 Result 类型
+pub type ControlResult<T> = std::result::Result<T, ControlError>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,5 +3,7 @@ pub mod constants;
 pub mod core;
 pub mod daemon;
 pub mod error;
+pub mod fsutil;
 pub mod logger;
+pub mod metrics;
 pub mod rsa;
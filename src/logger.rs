@@ -162,15 +162,29 @@ fn get_log_path() -> io::Result<PathBuf> {
     Ok(log_dir.join("shu-net-keeper.log"))
 }
 
-/// 初始化 tracing 日志系统
-pub fn init() -> Result<(), Box<dyn std::error::Error>> {
+/// 初始化 tracing 日志系统（同时输出到日志文件和终端）
+/// `level` 为日志级别或过滤表达式（如 `debug`），为 None 时使用 RUST_LOG，默认 info
+pub fn init(level: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    init_with_console(level, true)
+}
+
+/// 初始化 tracing 日志系统，只写入日志文件
+/// 用于标准输出需要保持干净的场景（如单次检查的 JSON / Nagios 输出）
+pub fn init_file_only(level: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    init_with_console(level, false)
+}
+
+fn init_with_console(level: Option<&str>, console: bool) -> Result<(), Box<dyn std::error::Error>> {
     let log_path = get_log_path()?;
 
     // 创建带行数限制的 Writer，使用 Arc 包装以共享所有权
     let file_writer = WriterWrapper(Arc::new(LimitedLineWriter::new(log_path.clone())?));
 
     // 配置日志级别过滤器
-    let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let env_filter = match level {
+        Some(level) => EnvFilter::try_new(level)?,
+        None => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+    };
 
     // 文件日志 layer
     let file_layer = tracing_subscriber::fmt::layer()
@@ -182,13 +196,15 @@ pub fn init() -> Result<(), Box<dyn std::error::Error>> {
         .with_span_events(FmtSpan::NONE);
 
     // 终端日志 layer
-    let stdout_layer = tracing_subscriber::fmt::layer()
-        .with_writer(std::io::stdout)
-        .with_ansi(true) // 终端日志使用颜色
-        .with_target(false)
-        .with_thread_ids(false)
-        .with_thread_names(false)
-        .with_span_events(FmtSpan::NONE);
+    let stdout_layer = console.then(|| {
+        tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stdout)
+            .with_ansi(true) // 终端日志使用颜色
+            .with_target(false)
+            .with_thread_ids(false)
+            .with_thread_names(false)
+            .with_span_events(FmtSpan::NONE)
+    });
 
     // 组合两个 layer
     tracing_subscriber::registry()
@@ -1,25 +1,68 @@
-mod config;
-mod constants;
-mod core;
-mod daemon;
-mod error;
-mod logger;
-pub mod rsa;
-
-use error::Result;
+mod cli;
+
+use clap::Parser;
+use cli::{Cli, Command};
+use shu_net_keeper::error::{self, Result};
+use shu_net_keeper::{config, daemon, logger};
 use tracing::{error, info};
 
 fn main() {
-    if let Err(e) = run() {
+    let cli = Cli::parse();
+
+    if let Err(e) = run(cli) {
         error!("程序运行失败: {}", e);
         eprintln!("✗ {}", e);
         std::process::exit(1);
     }
 }
 
-fn run() -> Result<()> {
+fn run(cli: Cli) -> Result<()> {
+    if let Some(path) = cli.config {
+        config::set_config_path(path);
+    }
+    let log_level = cli.log_level.as_deref();
+
+    match cli.command.unwrap_or_default() {
+        Command::Run => run_daemon(log_level),
+        Command::Login => {
+            init_file_logger(log_level);
+            cli::login::login(cli.json)
+        }
+        Command::Logout => {
+            init_file_logger(log_level);
+            cli::login::logout(cli.json)
+        }
+        Command::Status => cli::status::run(cli.json),
+        Command::Init { force, template } => cli::init::run(force, template, cli.json),
+        Command::Diagnose => {
+            init_file_logger(log_level);
+            cli::diagnose::run(cli.json)
+        }
+        Command::CheckConfig => cli::config::check(cli.json),
+        Command::MigrateConfig { dry_run } => cli::migrate::run(dry_run, cli.json),
+        Command::ConfigSchema => cli::config::schema(),
+        Command::EncryptPassword => cli::encrypt::run(cli.json),
+        Command::TestNotify => {
+            init_file_logger(log_level);
+            cli::config::test_notify(cli.json)
+        }
+        Command::Stats { days, weeks } => cli::stats::run(days, weeks, cli.json),
+        Command::Once { nagios } => std::process::exit(cli::once::run(nagios, cli.json, log_level)),
+        Command::Ctl { command } => cli::ctl::run(command, cli.json),
+        Command::Keyring { command } => cli::keyring::run(command, cli.json),
+    }
+}
+
+/// 一次性命令的日志只写入日志文件，终端只显示结果
+fn init_file_logger(level: Option<&str>) {
+    if let Err(e) = logger::init_file_only(level) {
+        eprintln!("✗ 日志系统初始化失败: {}", e);
+    }
+}
+
+fn run_daemon(log_level: Option<&str>) -> Result<()> {
     // 初始化日志系统
-    logger::init().map_err(|e| {
+    logger::init(log_level).map_err(|e| {
         eprintln!("✗ 日志系统初始化失败: {}", e);
         error::AppError::Other(format!("日志系统初始化失败: {}", e))
     })?;
@@ -0,0 +1,226 @@
+//! Prometheus 指标
+//!
+//! 计数器和直方图由守护循环与 `core` 中的门户请求记录到全局注册表，
+//! 连接状态等仪表值在导出时从守护进程状态快照中计算。
+
+use crate::daemon::StatusSnapshot;
+use chrono::{DateTime, Local};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+const PREFIX: &str = "shu_net_keeper";
+
+/// 门户请求耗时直方图的桶边界（秒）
+const LATENCY_BUCKETS: [f64; 8] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::default);
+
+/// 全局指标注册表
+pub fn global() -> &'static Metrics {
+    &METRICS
+}
+
+#[derive(Debug, Default, Clone)]
+struct Histogram {
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if value <= bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// 按结果统计的检查次数
+    checks: Mutex<BTreeMap<&'static str, u64>>,
+    /// 按 (结果, 原因) 统计的登录尝试次数
+    login_attempts: Mutex<BTreeMap<(&'static str, &'static str), u64>>,
+    ip_changes: AtomicU64,
+    /// 按门户接口统计的请求耗时
+    portal_latency: Mutex<BTreeMap<&'static str, Histogram>>,
+}
+
+impl Metrics {
+    /// 记录一次检查，`result` 为 online / logged_in / paused / idle / error
+    pub fn record_check(&self, result: &'static str) {
+        *self.checks.lock().unwrap().entry(result).or_default() += 1;
+    }
+
+    /// 记录一次登录尝试，成功时 `reason` 为 "ok"
+    pub fn record_login(&self, success: bool, reason: &'static str) {
+        let result = if success { "success" } else { "failure" };
+        *self
+            .login_attempts
+            .lock()
+            .unwrap()
+            .entry((result, reason))
+            .or_default() += 1;
+    }
+
+    pub fn record_ip_change(&self) {
+        self.ip_changes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 记录一次门户请求耗时（无论请求是否成功）
+    pub fn observe_portal_request(&self, endpoint: &'static str, elapsed: Duration) {
+        self.portal_latency
+            .lock()
+            .unwrap()
+            .entry(endpoint)
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// 以 Prometheus 文本格式导出全部指标
+    pub fn render(&self, status: Option<&StatusSnapshot>, now: DateTime<Local>) -> String {
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "checks_total",
+            "counter",
+            "网络检查次数（按结果）",
+        );
+        for (result, count) in self.checks.lock().unwrap().iter() {
+            let _ = writeln!(out, "{PREFIX}_checks_total{{result=\"{result}\"}} {count}");
+        }
+
+        header(
+            &mut out,
+            "login_attempts_total",
+            "counter",
+            "登录尝试次数（按结果和原因）",
+        );
+        for ((result, reason), count) in self.login_attempts.lock().unwrap().iter() {
+            let _ = writeln!(
+                out,
+                "{PREFIX}_login_attempts_total{{result=\"{result}\",reason=\"{reason}\"}} {count}"
+            );
+        }
+
+        header(
+            &mut out,
+            "ip_changes_total",
+            "counter",
+            "检测到的 IP 变化次数",
+        );
+        let _ = writeln!(
+            out,
+            "{PREFIX}_ip_changes_total {}",
+            self.ip_changes.load(Ordering::Relaxed)
+        );
+
+        header(
+            &mut out,
+            "portal_request_duration_seconds",
+            "histogram",
+            "校园网门户请求耗时（秒）",
+        );
+        for (endpoint, histogram) in self.portal_latency.lock().unwrap().iter() {
+            let name = format!("{PREFIX}_portal_request_duration_seconds");
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(histogram.buckets) {
+                let _ = writeln!(
+                    out,
+                    "{name}_bucket{{endpoint=\"{endpoint}\",le=\"{bound}\"}} {count}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{endpoint=\"{endpoint}\",le=\"+Inf\"}} {}",
+                histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "{name}_sum{{endpoint=\"{endpoint}\"}} {}",
+                histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "{name}_count{{endpoint=\"{endpoint}\"}} {}",
+                histogram.count
+            );
+        }
+
+        if let Some(status) = status {
+            header(
+                &mut out,
+                "connected",
+                "gauge",
+                "当前是否在线（1 在线，0 离线）",
+            );
+            let connected = u8::from(status.connected == Some(true));
+            let _ = writeln!(out, "{PREFIX}_connected {connected}");
+
+            header(&mut out, "paused", "gauge", "自动登录是否已暂停");
+            let _ = writeln!(out, "{PREFIX}_paused {}", u8::from(status.paused));
+
+            if let Some(at) = status.last_login_at {
+                header(
+                    &mut out,
+                    "seconds_since_last_login",
+                    "gauge",
+                    "距上次登录成功的秒数",
+                );
+                let _ = writeln!(
+                    out,
+                    "{PREFIX}_seconds_since_last_login {}",
+                    (now - at).num_seconds().max(0)
+                );
+            }
+        }
+
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {PREFIX}_{name} {help}");
+    let _ = writeln!(out, "# TYPE {PREFIX}_{name} {kind}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let metrics = Metrics::default();
+        metrics.record_check("online");
+        metrics.record_check("online");
+        metrics.record_login(false, "authentication");
+        metrics.record_ip_change();
+        metrics.observe_portal_request("login", Duration::from_millis(300));
+
+        let out = metrics.render(None, Local::now());
+
+        assert!(out.contains("shu_net_keeper_checks_total{result=\"online\"} 2"));
+        assert!(out.contains(
+            "shu_net_keeper_login_attempts_total{result=\"failure\",reason=\"authentication\"} 1"
+        ));
+        assert!(out.contains("shu_net_keeper_ip_changes_total 1"));
+        assert!(out.contains(
+            "shu_net_keeper_portal_request_duration_seconds_bucket{endpoint=\"login\",le=\"0.25\"} 0"
+        ));
+        assert!(out.contains(
+            "shu_net_keeper_portal_request_duration_seconds_bucket{endpoint=\"login\",le=\"0.5\"} 1"
+        ));
+        assert!(out.contains(
+            "shu_net_keeper_portal_request_duration_seconds_count{endpoint=\"login\"} 1"
+        ));
+        // 没有状态快照时不导出仪表值
+        assert!(!out.contains("shu_net_keeper_connected"));
+    }
+}
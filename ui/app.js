@@ -23,6 +23,165 @@ function togglePw(inputId, btn) {
     smtpFields.classList.toggle("hidden", !smtpEnabledBox.checked);
   });
 
+  // ── Account rule ─────────────────────────────────────────────────────────
+
+  const DEFAULT_ACCOUNT = { rule: "length", min_length: 8, max_length: 8, digits_only: true };
+
+  const accountRule    = document.getElementById("account-rule");
+  const accountLength  = document.getElementById("account-length");
+  const accountRegex   = document.getElementById("account-regex");
+  const usernameInput  = document.getElementById("username");
+  const usernameHint   = document.getElementById("username-hint");
+  const usernameError  = document.getElementById("username-error");
+
+  function applyAccount(account) {
+    accountRule.value = account.rule;
+    document.getElementById("account-min").value     = account.min_length ?? "";
+    document.getElementById("account-max").value     = account.max_length ?? "";
+    document.getElementById("account-digits").checked = account.digits_only ?? false;
+    document.getElementById("account-pattern").value = account.pattern ?? "";
+    updateAccountFields();
+  }
+
+  function readAccount() {
+    switch (accountRule.value) {
+      case "regex":
+        return { rule: "regex", pattern: document.getElementById("account-pattern").value };
+      case "none":
+        return { rule: "none" };
+      default:
+        return {
+          rule: "length",
+          min_length:  parseInt(document.getElementById("account-min").value, 10) || 0,
+          max_length:  parseInt(document.getElementById("account-max").value, 10) || 0,
+          digits_only: document.getElementById("account-digits").checked,
+        };
+    }
+  }
+
+  function describeAccount(account) {
+    switch (account.rule) {
+      case "regex": return "（匹配 " + account.pattern + "）";
+      case "none":  return "（不限制格式）";
+      default: {
+        const len = account.min_length === account.max_length
+          ? account.min_length
+          : account.min_length + "~" + account.max_length;
+        return "（" + len + "位" + (account.digits_only ? "数字" : "") + "）";
+      }
+    }
+  }
+
+  function updateAccountFields() {
+    accountLength.classList.toggle("hidden", accountRule.value !== "length");
+    accountRegex.classList.toggle("hidden", accountRule.value !== "regex");
+    usernameHint.textContent = describeAccount(readAccount());
+  }
+
+  // 由后端按同一套规则检查，保证与配置验证结果一致
+  async function checkUsername() {
+    const username = usernameInput.value.trim();
+    if (!username) {
+      usernameError.textContent = "";
+      return;
+    }
+    try {
+      await invoke("validate_username", { username, account: readAccount() });
+      usernameError.textContent = "";
+    } catch (e) {
+      usernameError.textContent = String(e);
+    }
+  }
+
+  accountRule.addEventListener("change", () => { updateAccountFields(); checkUsername(); });
+  for (const id of ["account-min", "account-max", "account-digits", "account-pattern"]) {
+    document.getElementById(id).addEventListener("input", () => { updateAccountFields(); checkUsername(); });
+  }
+  usernameInput.addEventListener("input", checkUsername);
+  applyAccount(DEFAULT_ACCOUNT);
+
+  // ── Config schema ────────────────────────────────────────────────────────
+
+  // 表单约束来自后端生成的 JSON Schema，与配置验证保持一致
+  const SCHEMA_FIELDS = {
+    "username":      ["username"],
+    "interval":      ["interval"],
+    "smtp-server":   ["smtp", "server"],
+    "smtp-port":     ["smtp", "port"],
+    "smtp-sender":   ["smtp", "sender"],
+    "smtp-password": ["smtp", "password"],
+    "smtp-receiver": ["smtp", "receiver"],
+  };
+  const EMAIL_RE = /^[^\s@]+@[^\s@]+\.[^\s@]+$/;
+
+  let schema = null;
+
+  // 展开 $ref 和 Option 生成的 anyOf
+  function resolveSchema(node) {
+    while (node) {
+      if (node.$ref) {
+        node = schema.$defs[node.$ref.replace("#/$defs/", "")];
+      } else if (node.anyOf) {
+        node = node.anyOf.find((s) => s.type !== "null");
+      } else {
+        return node;
+      }
+    }
+    return node;
+  }
+
+  function fieldSchema(path) {
+    let node = schema;
+    for (const key of path) {
+      node = resolveSchema(resolveSchema(node)?.properties?.[key]);
+    }
+    return node ?? {};
+  }
+
+  function fieldLabel(id) {
+    const label = document.getElementById(id).closest(".form-row").querySelector("label");
+    return label.firstChild.textContent.trim();
+  }
+
+  async function loadSchema() {
+    try {
+      schema = await invoke("get_config_schema");
+    } catch (e) {
+      console.error("load schema failed:", e);
+      return;
+    }
+    for (const [id, path] of Object.entries(SCHEMA_FIELDS)) {
+      const el = document.getElementById(id);
+      const s = fieldSchema(path);
+      if (s.description) el.title = s.description;
+      if (s.minimum !== undefined) el.min = s.minimum;
+      if (s.maximum !== undefined) el.max = s.maximum;
+      if (s.default != null) el.placeholder = s.default;
+    }
+  }
+
+  // 按 Schema 检查表单，返回问题列表
+  function schemaErrors(cfg) {
+    if (!schema) return [];
+    const errors = [];
+    const required = cfg.smtp_enabled ? (schema.then?.properties?.smtp?.required ?? []) : [];
+
+    for (const [id, path] of Object.entries(SCHEMA_FIELDS)) {
+      const value = path.reduce((obj, key) => obj?.[key], cfg);
+      const s = fieldSchema(path);
+      const label = fieldLabel(id);
+      if (value == null || value === "") {
+        if (path[0] === "smtp" && required.includes(path[1])) errors.push(label + " 为必填项");
+        continue;
+      }
+      if (s.minLength !== undefined && String(value).length < s.minLength) errors.push(label + " 不能为空");
+      if (s.minimum !== undefined && value < s.minimum) errors.push(label + " 不能小于 " + s.minimum);
+      if (s.maximum !== undefined && value > s.maximum) errors.push(label + " 不能大于 " + s.maximum);
+      if (s.format === "email" && !EMAIL_RE.test(value)) errors.push(label + " 格式不正确");
+    }
+    return errors;
+  }
+
   // ── Load config ──────────────────────────────────────────────────────────
 
   async function loadConfig() {
@@ -30,8 +189,10 @@ function togglePw(inputId, btn) {
       const cfg = await invoke("get_config");
       if (!cfg) return;
       document.getElementById("username").value = cfg.username ?? "";
+      applyAccount(cfg.account ?? DEFAULT_ACCOUNT);
+      checkUsername();
       document.getElementById("password").value = cfg.password ?? "";
-      document.getElementById("interval").value = cfg.interval ?? 600;
+      document.getElementById("interval").value = cfg.interval ?? "";
       smtpEnabledBox.checked = cfg.smtp_enabled ?? false;
       smtpFields.classList.toggle("hidden", !smtpEnabledBox.checked);
       if (cfg.smtp) {
@@ -52,8 +213,9 @@ function togglePw(inputId, btn) {
     const smtpEnabled = smtpEnabledBox.checked;
     const cfg = {
       username:     document.getElementById("username").value.trim(),
+      account:      readAccount(),
       password:     document.getElementById("password").value,
-      interval:     parseInt(document.getElementById("interval").value, 10) || 600,
+      interval:     parseInt(document.getElementById("interval").value, 10) || fieldSchema(["interval"]).default || 10,
       smtp_enabled: smtpEnabled,
       smtp: smtpEnabled ? {
         server:   document.getElementById("smtp-server").value.trim()   || null,
@@ -65,9 +227,16 @@ function togglePw(inputId, btn) {
     };
 
     const msg = document.getElementById("save-msg");
+    const errors = schemaErrors(cfg);
+    if (usernameError.textContent) errors.unshift(usernameError.textContent);
+    if (errors.length) {
+      msg.textContent = "✗ " + errors.join("；");
+      msg.className = "save-msg show error";
+      return;
+    }
     try {
-      await invoke("save_config", { config: cfg });
-      msg.textContent = "✓ 已保存";
+      const result = await invoke("save_config", { config: cfg });
+      msg.textContent = result.applied ? "✓ 已保存并应用到运行中的守护进程" : "✓ 已保存";
       msg.className = "save-msg show";
       setTimeout(() => msg.classList.remove("show"), 2000);
     } catch (e) {
@@ -86,6 +255,11 @@ function togglePw(inputId, btn) {
     const errBox   = document.getElementById("error-box");
     const btnStart = document.getElementById("btn-start");
     const btnStop  = document.getElementById("btn-stop");
+    const btnPause  = document.getElementById("btn-pause");
+    const btnResume = document.getElementById("btn-resume");
+
+    btnPause.classList.toggle("hidden", !s.running || s.paused);
+    btnResume.classList.toggle("hidden", !s.running || !s.paused);
 
     document.getElementById("metric-logins").textContent     = s.login_count ?? 0;
     document.getElementById("metric-last-check").textContent = s.last_check  ?? "—";
@@ -103,16 +277,25 @@ function togglePw(inputId, btn) {
       dot.className   = "conn-dot dot-connected";
       label.textContent = "已连接";
       ip.textContent    = s.ip ? "IP: " + s.ip : "—";
-      badge.textContent = "运行中";
+      badge.textContent = s.paused ? "已暂停" + pauseText(s) : "运行中";
       badge.className   = "badge badge-running";
       btnStart.classList.add("hidden");
       btnStop.classList.remove("hidden");
       errBox.classList.add("hidden");
+    } else if (s.paused && !s.last_error) {
+      dot.className   = "conn-dot dot-idle";
+      label.textContent = "未连接（自动登录已暂停）";
+      ip.textContent    = "—";
+      badge.textContent = "已暂停" + pauseText(s);
+      badge.className   = "badge badge-idle";
+      btnStart.classList.add("hidden");
+      btnStop.classList.remove("hidden");
+      errBox.classList.add("hidden");
     } else {
       dot.className   = "conn-dot dot-error";
       label.textContent = "未连接 / 重试中...";
       ip.textContent    = s.last_error ? "错误" : "—";
-      badge.textContent = "运行中";
+      badge.textContent = s.paused ? "已暂停" + pauseText(s) : "运行中";
       badge.className   = "badge badge-running";
       btnStart.classList.add("hidden");
       btnStop.classList.remove("hidden");
@@ -125,10 +308,66 @@ function togglePw(inputId, btn) {
     }
   }
 
+  function pauseText(s) {
+    if (s.pause_remaining_secs == null) return "";
+    return "（剩余 " + formatDuration(s.pause_remaining_secs) + "）";
+  }
+
   async function refreshStatus() {
     try { applyStatus(await invoke("get_status")); } catch (_) {}
   }
 
+  // ── Statistics ───────────────────────────────────────────────────────────
+
+  function formatDuration(secs) {
+    if (secs == null) return "—";
+    const h = Math.floor(secs / 3600);
+    const m = Math.floor((secs % 3600) / 60);
+    const s = secs % 60;
+    if (h > 0) return `${h}h${String(m).padStart(2, "0")}m`;
+    if (m > 0) return `${m}m${String(s).padStart(2, "0")}s`;
+    return `${s}s`;
+  }
+
+  function formatPercent(ratio) {
+    return (ratio * 100).toFixed(2) + "%";
+  }
+
+  function applyStats(r) {
+    const week = r.weekly[0];
+    if (week) {
+      document.getElementById("stats-week-availability").textContent = formatPercent(week.availability);
+      document.getElementById("stats-week-outages").textContent       = week.outage_count;
+      document.getElementById("stats-week-mttr").textContent          = formatDuration(week.mttr_secs);
+    }
+
+    const current = document.getElementById("stats-current");
+    if (r.current_outage) {
+      const since = new Date(r.current_outage.start).toLocaleString("zh-CN");
+      current.textContent = `当前断网中，开始于 ${since}（${r.current_outage.cause}）`;
+      current.classList.remove("hidden");
+    } else {
+      current.classList.add("hidden");
+    }
+
+    const tbody = document.getElementById("stats-daily");
+    tbody.innerHTML = "";
+    r.daily.forEach((d) => {
+      const tr = document.createElement("tr");
+      [d.label.slice(5), d.outage_count, d.auto_recovered_count,
+       formatPercent(d.availability), formatDuration(d.mttr_secs)].forEach((v) => {
+        const td = document.createElement("td");
+        td.textContent = v;
+        tr.appendChild(td);
+      });
+      tbody.appendChild(tr);
+    });
+  }
+
+  async function refreshStats() {
+    try { applyStats(await invoke("get_stats")); } catch (_) {}
+  }
+
   // ── Daemon controls ──────────────────────────────────────────────────────
 
   document.getElementById("btn-start").addEventListener("click", async () => {
@@ -143,6 +382,22 @@ function togglePw(inputId, btn) {
     await invoke("stop_daemon");
   });
 
+  document.getElementById("btn-pause").addEventListener("click", async () => {
+    try {
+      await invoke("pause_daemon", { minutes: 30 });
+    } catch (e) {
+      alert("暂停失败: " + String(e));
+    }
+  });
+
+  document.getElementById("btn-resume").addEventListener("click", async () => {
+    try {
+      await invoke("resume_daemon");
+    } catch (e) {
+      alert("恢复失败: " + String(e));
+    }
+  });
+
   // ── Logs ─────────────────────────────────────────────────────────────────
 
   const logContainer = document.getElementById("log-container");
@@ -187,7 +442,7 @@ function togglePw(inputId, btn) {
 
   // ── Real-time events ─────────────────────────────────────────────────────
 
-  await listen("status-update", (e) => applyStatus(e.payload));
+  await listen("status-update", (e) => { applyStatus(e.payload); refreshStats(); });
   await listen("log-entry",     (e) => appendLog(e.payload));
 
   // ── Autostart ────────────────────────────────────────────────────────────
@@ -205,10 +460,13 @@ function togglePw(inputId, btn) {
 
   // ── Init ─────────────────────────────────────────────────────────────────
 
+  await loadSchema();
   await loadConfig();
   await refreshStatus();
+  await refreshStats();
   await loadLogs();
   try { autostartToggle.checked = await invoke("get_autostart"); } catch (_) {}
 
   setInterval(refreshStatus, 5000);
+  setInterval(refreshStats, 60000);
 })();